@@ -0,0 +1,55 @@
+/*
+ * IPASIR interface of the dissat SAT solver.
+ *
+ * Link against the `cdylib` (libdissat.so) or `staticlib` (libdissat.a)
+ * produced by `cargo build --release`.
+ */
+#ifndef DISSAT_IPASIR_H
+#define DISSAT_IPASIR_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Name and version of the solver. */
+const char *ipasir_signature(void);
+
+/* Construct a new solver and return a pointer to it. */
+void *ipasir_init(void);
+
+/* Release the solver, i.e., all its resources are freed. */
+void ipasir_release(void *solver);
+
+/* Add the given literal into the currently added clause or finalize the clause with a 0. */
+void ipasir_add(void *solver, int32_t lit_or_zero);
+
+/* Add an assumption for the next SAT search (the next call of ipasir_solve). */
+void ipasir_assume(void *solver, int32_t lit);
+
+/* Solve the formula with the specified clauses under the specified assumptions.
+ * Returns 10 if satisfiable, 20 if unsatisfiable and 0 if the search was interrupted. */
+int ipasir_solve(void *solver);
+
+/* Get the truth value of the given literal in the found satisfying assignment.
+ * Returns 'lit' if True, '-lit' if False and 0 if the variable is unknown. */
+int32_t ipasir_val(void *solver, int32_t lit);
+
+/* Check if the given assumption literal was used to prove the unsatisfiability
+ * of the formula under the assumptions used for the last SAT search. */
+int ipasir_failed(void *solver, int32_t lit);
+
+/* Set a callback function used to indicate a termination requirement to the solver.
+ * The solver stops the search as soon as the callback returns a non-zero value. */
+void ipasir_set_terminate(void *solver, void *data, int (*terminate)(void *data));
+
+/* Set a callback function used to extract learned clauses up to a given length.
+ * The clause is passed as a zero terminated array of literals. */
+void ipasir_set_learn(void *solver, void *data, int max_length, void (*learn)(void *data, int32_t *clause));
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
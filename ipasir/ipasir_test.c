@@ -0,0 +1,157 @@
+/*
+ * Drives the IPASIR interface with a DIMACS file.
+ *
+ * usage: ipasir_test <input.cnf> [<expected.sol>]
+ *
+ * The model is checked against the clauses of the input. If a solution file is given,
+ * the result has to match its `s SATISFIABLE` / `s UNSATISFIABLE` line.
+ * Afterwards, the formula is solved again incrementally, assuming the negation of a clause.
+ */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "ipasir.h"
+
+struct formula {
+    int32_t *lits; /* all clauses, each terminated by 0 */
+    size_t len;
+    size_t cap;
+};
+
+static void push(struct formula *f, int32_t lit) {
+    if (f->len == f->cap) {
+        f->cap = f->cap ? 2 * f->cap : 1024;
+        f->lits = realloc(f->lits, f->cap * sizeof(int32_t));
+        if (!f->lits) {
+            perror("realloc");
+            exit(2);
+        }
+    }
+    f->lits[f->len++] = lit;
+}
+
+static void parse(FILE *file, struct formula *f) {
+    int c;
+    while ((c = fgetc(file)) != EOF) {
+        if (c == 'c' || c == 'p') {
+            while ((c = fgetc(file)) != EOF && c != '\n') {
+            }
+            continue;
+        }
+        if (c == ' ' || c == '\t' || c == '\n' || c == '\r') {
+            continue;
+        }
+        ungetc(c, file);
+        int32_t lit;
+        if (fscanf(file, "%d", &lit) != 1) {
+            fprintf(stderr, "malformed dimacs input\n");
+            exit(2);
+        }
+        push(f, lit);
+    }
+}
+
+/* Returns 10 or 20 depending on the s line of the solution file, 0 if there is none. */
+static int expected_result(const char *path) {
+    FILE *file = fopen(path, "r");
+    if (!file) {
+        return 0;
+    }
+    char line[256];
+    int result = 0;
+    while (fgets(line, sizeof(line), file)) {
+        if (strncmp(line, "s SATISFIABLE", 13) == 0) {
+            result = 10;
+        } else if (strncmp(line, "s UNSATISFIABLE", 15) == 0) {
+            result = 20;
+        }
+    }
+    fclose(file);
+    return result;
+}
+
+static int check_model(void *solver, const struct formula *f) {
+    int satisfied = 0;
+    for (size_t i = 0; i < f->len; i++) {
+        int32_t lit = f->lits[i];
+        if (lit == 0) {
+            if (!satisfied) {
+                return 0;
+            }
+            satisfied = 0;
+        } else if (ipasir_val(solver, lit) == lit) {
+            satisfied = 1;
+        }
+    }
+    return 1;
+}
+
+int main(int argc, char **argv) {
+    if (argc < 2) {
+        fprintf(stderr, "usage: %s <input.cnf> [<expected.sol>]\n", argv[0]);
+        return 2;
+    }
+
+    FILE *file = fopen(argv[1], "r");
+    if (!file) {
+        perror(argv[1]);
+        return 2;
+    }
+    struct formula f = {0};
+    parse(file, &f);
+    fclose(file);
+
+    void *solver = ipasir_init();
+    for (size_t i = 0; i < f.len; i++) {
+        ipasir_add(solver, f.lits[i]);
+    }
+
+    int result = ipasir_solve(solver);
+    int expected = argc > 2 ? expected_result(argv[2]) : 0;
+
+    if (result != 10 && result != 20) {
+        fprintf(stderr, "%s: unexpected result %d\n", argv[1], result);
+        return 1;
+    }
+    if (expected && result != expected) {
+        fprintf(stderr, "%s: expected %d, got %d\n", argv[1], expected, result);
+        return 1;
+    }
+    if (result == 10 && !check_model(solver, &f)) {
+        fprintf(stderr, "%s: model doesn't satisfy the formula\n", argv[1]);
+        return 1;
+    }
+
+    if (result == 10 && f.len > 0) {
+        /* Assuming the negation of the first clause has to be unsatisfiable
+         * and at least one of the assumptions has to be part of the failed set. */
+        for (size_t i = 0; f.lits[i] != 0; i++) {
+            ipasir_assume(solver, -f.lits[i]);
+        }
+        if (ipasir_solve(solver) != 20) {
+            fprintf(stderr, "%s: negated clause is not unsatisfiable\n", argv[1]);
+            return 1;
+        }
+        int failed = 0;
+        for (size_t i = 0; f.lits[i] != 0; i++) {
+            failed |= ipasir_failed(solver, -f.lits[i]);
+        }
+        if (!failed) {
+            fprintf(stderr, "%s: no failed assumption reported\n", argv[1]);
+            return 1;
+        }
+
+        /* Without assumptions, the formula is satisfiable again. */
+        if (ipasir_solve(solver) != 10 || !check_model(solver, &f)) {
+            fprintf(stderr, "%s: incremental call failed\n", argv[1]);
+            return 1;
+        }
+    }
+
+    printf("%s: %s (%s)\n", argv[1], result == 10 ? "SAT" : "UNSAT", ipasir_signature());
+
+    ipasir_release(solver);
+    free(f.lits);
+    return 0;
+}
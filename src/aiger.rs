@@ -0,0 +1,362 @@
+//! Reader for the ASCII (`aag`) and binary (`aig`) AIGER format.
+//!
+//! AIGER literals are `2 * variable + negation`, where variable 0 is the constant false.
+//! [`Aig::frame`] builds the combinational logic of one time step into a [`Circuit`].
+
+use std::{collections::HashMap, error::Error};
+
+use crate::circuit::{Circuit, Signal};
+
+/// Parser for the AIGER format.
+pub struct Aiger;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Latch {
+    pub lit: u32,
+    pub next: u32,
+    /// Initial value: `0`, `1` or `lit` for an uninitialized latch.
+    pub reset: u32,
+}
+
+/// And gate `lhs = rhs0 ∧ rhs1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AndGate {
+    pub lhs: u32,
+    pub rhs0: u32,
+    pub rhs1: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Aig {
+    pub max_var: u32,
+    pub inputs: Vec<u32>,
+    pub latches: Vec<Latch>,
+    pub outputs: Vec<u32>,
+    pub bad: Vec<u32>,
+    pub constraints: Vec<u32>,
+    /// And gates ordered, such that every gate is defined after its inputs.
+    pub ands: Vec<AndGate>,
+}
+
+/// Signals of one time step of an [`Aig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub inputs: Vec<Signal>,
+    pub latches: Vec<Signal>,
+    pub outputs: Vec<Signal>,
+    pub bad: Vec<Signal>,
+    pub constraints: Vec<Signal>,
+    /// Next state of each latch.
+    pub next: Vec<Signal>,
+}
+
+/// Cursor over the input, which reads ASCII lines and binary encoded numbers.
+struct Reader<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn line(&mut self) -> Result<&'a str, Box<dyn Error>> {
+        if self.pos >= self.input.len() {
+            return Err("unexpected end of input".into());
+        }
+
+        let rest = &self.input[self.pos..];
+        let len = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+        self.pos += len + 1;
+        Ok(std::str::from_utf8(&rest[..len])?.trim())
+    }
+
+    /// Line of exactly `count` numbers.
+    fn numbers(&mut self, count: usize) -> Result<Vec<u32>, Box<dyn Error>> {
+        let line = self.line()?;
+        let numbers = line
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<u32>, _>>()?;
+        if numbers.len() != count {
+            return Err(format!("expected {count} numbers, found `{line}`").into());
+        }
+        Ok(numbers)
+    }
+
+    /// Number encoded in 7 bit groups, least significant group first.
+    fn binary_number(&mut self) -> Result<u32, Box<dyn Error>> {
+        let mut number: u32 = 0;
+        for shift in (0..32).step_by(7) {
+            let &byte = self
+                .input
+                .get(self.pos)
+                .ok_or("unexpected end of binary and gates")?;
+            self.pos += 1;
+
+            number |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(number);
+            }
+        }
+        Err("binary number too large".into())
+    }
+}
+
+impl Aiger {
+    pub fn parse(input: &[u8]) -> Result<Aig, Box<dyn Error>> {
+        let mut reader = Reader { input, pos: 0 };
+
+        let header = reader.line()?;
+        let mut tokens = header.split_whitespace();
+        let binary = match tokens.next() {
+            Some("aag") => false,
+            Some("aig") => true,
+            _ => return Err(format!("invalid header `{header}`").into()),
+        };
+        let counts = tokens.map(str::parse).collect::<Result<Vec<u32>, _>>()?;
+        if !(5..=9).contains(&counts.len()) {
+            return Err(format!("invalid header `{header}`").into());
+        }
+        let count = |idx: usize| counts.get(idx).copied().unwrap_or(0);
+        let (max_var, num_inputs, num_latches, num_outputs, num_ands) =
+            (count(0), count(1), count(2), count(3), count(4));
+        if count(7) != 0 || count(8) != 0 {
+            return Err("justice and fairness properties are not supported".into());
+        }
+
+        let mut aig = Aig {
+            max_var,
+            ..Aig::default()
+        };
+
+        for idx in 0..num_inputs {
+            if binary {
+                aig.inputs.push(2 * (idx + 1));
+            } else {
+                aig.inputs.push(reader.numbers(1)?[0]);
+            }
+        }
+
+        for idx in 0..num_latches {
+            let line = reader.line()?;
+            let numbers = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<Vec<u32>, _>>()?;
+            let (lit, numbers) = if binary {
+                (2 * (num_inputs + idx + 1), &numbers[..])
+            } else {
+                let (&lit, numbers) = numbers.split_first().ok_or("empty latch line")?;
+                (lit, numbers)
+            };
+            let latch = match *numbers {
+                [next] => Latch {
+                    lit,
+                    next,
+                    reset: 0,
+                },
+                [next, reset] => Latch { lit, next, reset },
+                _ => return Err(format!("invalid latch `{line}`").into()),
+            };
+            if ![0, 1, lit].contains(&latch.reset) {
+                return Err(format!("invalid reset value of latch `{line}`").into());
+            }
+            aig.latches.push(latch);
+        }
+
+        for _ in 0..num_outputs {
+            aig.outputs.push(reader.numbers(1)?[0]);
+        }
+        for _ in 0..count(5) {
+            aig.bad.push(reader.numbers(1)?[0]);
+        }
+        for _ in 0..count(6) {
+            aig.constraints.push(reader.numbers(1)?[0]);
+        }
+
+        let mut ands = Vec::with_capacity(num_ands as usize);
+        for idx in 0..num_ands {
+            let gate = if binary {
+                let lhs = 2 * (num_inputs + num_latches + idx + 1);
+                let rhs0 = lhs
+                    .checked_sub(reader.binary_number()?)
+                    .ok_or("invalid binary and gate")?;
+                let rhs1 = rhs0
+                    .checked_sub(reader.binary_number()?)
+                    .ok_or("invalid binary and gate")?;
+                AndGate { lhs, rhs0, rhs1 }
+            } else {
+                let numbers = reader.numbers(3)?;
+                AndGate {
+                    lhs: numbers[0],
+                    rhs0: numbers[1],
+                    rhs1: numbers[2],
+                }
+            };
+            ands.push(gate);
+        }
+        // The symbol table and comments are ignored.
+
+        aig.ands = Self::sort_ands(&aig, ands)?;
+        Ok(aig)
+    }
+
+    /// Check the definitions and order the and gates topologically.
+    fn sort_ands(aig: &Aig, ands: Vec<AndGate>) -> Result<Vec<AndGate>, Box<dyn Error>> {
+        let mut defined = vec![false; aig.max_var as usize + 1];
+        defined[0] = true;
+        let inputs = aig.inputs.iter().chain(aig.latches.iter().map(|l| &l.lit));
+        for &lit in inputs {
+            if lit & 1 == 1 || lit < 2 || lit / 2 > aig.max_var || defined[lit as usize / 2] {
+                return Err(format!("invalid input or latch literal {lit}").into());
+            }
+            defined[lit as usize / 2] = true;
+        }
+
+        let mut gates = HashMap::new();
+        for gate in ands {
+            let var = gate.lhs / 2;
+            if gate.lhs & 1 == 1 || var > aig.max_var || defined[var as usize] {
+                return Err(format!("invalid and gate literal {}", gate.lhs).into());
+            }
+            if gates.insert(var, gate).is_some() {
+                return Err(format!("and gate {} is defined twice", gate.lhs).into());
+            }
+        }
+
+        let mut sorted = Vec::with_capacity(gates.len());
+        let mut visiting = vec![false; aig.max_var as usize + 1];
+        let mut roots: Vec<u32> = gates.keys().copied().collect();
+        roots.sort_unstable();
+        for root in roots {
+            let mut stack = vec![root];
+            while let Some(&var) = stack.last() {
+                if defined[var as usize] {
+                    stack.pop();
+                    continue;
+                }
+                let gate = gates
+                    .get(&var)
+                    .ok_or_else(|| format!("literal {} is undefined", 2 * var))?;
+                visiting[var as usize] = true;
+
+                let pending: Vec<u32> = [gate.rhs0 / 2, gate.rhs1 / 2]
+                    .into_iter()
+                    .filter(|&input| input > aig.max_var || !defined[input as usize])
+                    .collect();
+                if pending.is_empty() {
+                    defined[var as usize] = true;
+                    sorted.push(*gate);
+                    stack.pop();
+                    continue;
+                }
+                for input in pending {
+                    if input > aig.max_var || visiting[input as usize] {
+                        return Err(format!("literal {} is undefined or cyclic", 2 * input).into());
+                    }
+                    stack.push(input);
+                }
+            }
+        }
+
+        let used = aig
+            .latches
+            .iter()
+            .map(|latch| latch.next)
+            .chain(aig.outputs.iter().copied())
+            .chain(aig.bad.iter().copied())
+            .chain(aig.constraints.iter().copied());
+        for lit in used {
+            if lit / 2 > aig.max_var || !defined[lit as usize / 2] {
+                return Err(format!("literal {lit} is undefined").into());
+            }
+        }
+
+        Ok(sorted)
+    }
+}
+
+impl Aig {
+    /// Build the combinational logic for the given signals of the inputs and the current state of
+    /// the latches into `circuit`.
+    pub fn frame(&self, circuit: &mut Circuit, inputs: Vec<Signal>, latches: Vec<Signal>) -> Frame {
+        assert_eq!(inputs.len(), self.inputs.len());
+        assert_eq!(latches.len(), self.latches.len());
+
+        let mut values = vec![Signal::FALSE; self.max_var as usize + 1];
+        for (&lit, &signal) in self.inputs.iter().zip(&inputs) {
+            values[lit as usize / 2] = signal;
+        }
+        for (latch, &signal) in self.latches.iter().zip(&latches) {
+            values[latch.lit as usize / 2] = signal;
+        }
+
+        let signal = |values: &[Signal], lit: u32| {
+            let signal = values[lit as usize / 2];
+            if lit & 1 == 1 {
+                !signal
+            } else {
+                signal
+            }
+        };
+
+        for gate in &self.ands {
+            let (a, b) = (signal(&values, gate.rhs0), signal(&values, gate.rhs1));
+            values[gate.lhs as usize / 2] = circuit.and(a, b);
+        }
+
+        let signals = |lits: &[u32]| lits.iter().map(|&lit| signal(&values, lit)).collect();
+        Frame {
+            inputs,
+            latches,
+            outputs: signals(&self.outputs),
+            bad: signals(&self.bad),
+            constraints: signals(&self.constraints),
+            next: self
+                .latches
+                .iter()
+                .map(|latch| signal(&values, latch.next))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Aiger, AndGate, Latch};
+
+    #[test]
+    fn parse_ascii_and_binary() {
+        // Toggle flip-flop with an enable input, whose output is true in state 1.
+        let ascii = "aag 3 1 1 1 1\n2\n4 6\n4\n6 5 3\nc\ntoggle\n";
+        let aig = Aiger::parse(ascii.as_bytes()).unwrap();
+        assert_eq!(aig.inputs, vec![2]);
+        assert_eq!(
+            aig.latches,
+            vec![Latch {
+                lit: 4,
+                next: 6,
+                reset: 0
+            }]
+        );
+        assert_eq!(aig.outputs, vec![4]);
+        assert_eq!(
+            aig.ands,
+            vec![AndGate {
+                lhs: 6,
+                rhs0: 5,
+                rhs1: 3
+            }]
+        );
+
+        let mut binary = b"aig 3 1 1 1 1\n6\n4\n".to_vec();
+        binary.extend([6 - 5, 5 - 3]);
+        assert_eq!(Aiger::parse(&binary).unwrap(), aig);
+    }
+
+    #[test]
+    fn reject_invalid() {
+        assert!(Aiger::parse(b"aag 1 0 0 1 0\n4\n").is_err());
+        assert!(Aiger::parse(b"aag 2 0 0 0 2\n2 4 1\n4 2 1\n").is_err());
+        assert!(Aiger::parse(b"aag 1 1 0 0 0\n3\n").is_err());
+        assert!(Aiger::parse(b"aig 1 0 0 0 1\n").is_err());
+    }
+}
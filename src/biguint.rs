@@ -0,0 +1,199 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, AddAssign, Mul, Shl},
+};
+
+/// Arbitrary precision unsigned integer, e.g. for model counts.
+///
+/// The value is stored as 32 bit limbs with the least significant limb first and without leading
+/// zero limbs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::from(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn normalize(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    /// The value, if it fits into `u64`.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [low] => Some(low as u64),
+            [low, high] => Some((high as u64) << 32 | low as u64),
+            _ => None,
+        }
+    }
+
+    /// Divide by the small `divisor` in place and return the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = remainder << 32 | *limb as u64;
+            *limb = (value / divisor as u64) as u32;
+            remainder = value % divisor as u64;
+        }
+        *self = std::mem::take(self).normalize();
+        remainder as u32
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        }
+        .normalize()
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0u64;
+        for (idx, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + other.limbs.get(idx).copied().unwrap_or(0) as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut sum = self.clone();
+        sum += other;
+        sum
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let product = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint { limbs }.normalize()
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        if self.is_zero() {
+            return self;
+        }
+
+        let (limb_shift, bit_shift) = (shift / 32, shift % 32);
+        let mut limbs = vec![0u32; limb_shift];
+        let mut carry = 0u32;
+        for limb in self.limbs {
+            limbs.push(limb << bit_shift | carry);
+            carry = if bit_shift == 0 {
+                0
+            } else {
+                limb >> (32 - bit_shift)
+            };
+        }
+        limbs.push(carry);
+        BigUint { limbs }.normalize()
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+
+        // Decimal chunks of nine digits, least significant first.
+        let mut value = self.clone();
+        let mut chunks = Vec::new();
+        while !value.is_zero() {
+            chunks.push(value.div_rem_small(CHUNK));
+        }
+
+        let mut chunks = chunks.iter().rev();
+        let mut digits = chunks.next().copied().unwrap_or(0).to_string();
+        for chunk in chunks {
+            digits.push_str(&format!("{chunk:09}"));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BigUint;
+
+    #[test]
+    fn arithmetic() {
+        let factorial = (1..=30).fold(BigUint::one(), |acc, n| &acc * &BigUint::from(n));
+        assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+
+        let power = BigUint::one() << 100;
+        assert_eq!(power.to_string(), "1267650600228229401496703205376");
+        assert_eq!(
+            (&power + &power).to_string(),
+            "2535301200456458802993406410752"
+        );
+        assert!(power < factorial);
+
+        assert_eq!(BigUint::from(u64::MAX).to_u64(), Some(u64::MAX));
+        assert_eq!((BigUint::from(u64::MAX) << 1).to_u64(), None);
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!((BigUint::zero() << 7).to_string(), "0");
+    }
+}
@@ -0,0 +1,250 @@
+//! Unsigned bit-vectors built from the signals of a [`Circuit`].
+//!
+//! Arithmetic grows the result, so that it never overflows. Use [`BitVec::truncate`] for modular
+//! arithmetic. Operands of different widths are zero extended.
+
+use crate::{
+    circuit::{Circuit, Signal},
+    encodings::ClauseSink,
+    Model,
+};
+
+/// Bits of an unsigned integer with the least significant bit first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitVec {
+    bits: Vec<Signal>,
+}
+
+impl BitVec {
+    pub fn from_signals(bits: Vec<Signal>) -> Self {
+        Self { bits }
+    }
+
+    /// Bit-vector of fresh variables allocated by `sink`.
+    pub fn new_input(circuit: &mut Circuit, sink: &mut impl ClauseSink, width: usize) -> Self {
+        Self::from_signals((0..width).map(|_| circuit.new_input(sink)).collect())
+    }
+
+    /// Bit-vector of the solver literals `lits`, least significant bit first.
+    pub fn from_lits(circuit: &mut Circuit, lits: &[i32]) -> Self {
+        Self::from_signals(lits.iter().map(|&lit| circuit.lit(lit)).collect())
+    }
+
+    pub fn constant(value: u64, width: usize) -> Self {
+        assert!(
+            width >= 64 || value >> width == 0,
+            "{value} needs more than {width} bits"
+        );
+
+        let bits = (0..width)
+            .map(|bit| bit < 64 && value >> bit & 1 == 1)
+            .map(|value| if value { Signal::TRUE } else { Signal::FALSE })
+            .collect();
+        Self::from_signals(bits)
+    }
+
+    pub fn width(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn bits(&self) -> &[Signal] {
+        &self.bits
+    }
+
+    /// Bit `idx`, which is false beyond the width.
+    pub fn bit(&self, idx: usize) -> Signal {
+        self.bits.get(idx).copied().unwrap_or(Signal::FALSE)
+    }
+
+    pub fn zero_extend(&self, width: usize) -> Self {
+        Self::from_signals(
+            (0..width.max(self.width()))
+                .map(|idx| self.bit(idx))
+                .collect(),
+        )
+    }
+
+    /// The `width` least significant bits.
+    pub fn truncate(&self, width: usize) -> Self {
+        Self::from_signals(self.bits.iter().copied().take(width).collect())
+    }
+
+    /// Integer value under the assignment of `model`. The width has to be at most 64 bits.
+    pub fn value(&self, circuit: &Circuit, model: &Model) -> u64 {
+        assert!(
+            self.width() <= 64,
+            "{} bits don't fit into u64",
+            self.width()
+        );
+
+        self.bits
+            .iter()
+            .enumerate()
+            .filter(|(_, &bit)| circuit.value(bit, model))
+            .fold(0, |value, (idx, _)| value | 1 << idx)
+    }
+}
+
+impl Circuit {
+    /// Sum and carry of a full adder.
+    fn full_adder(&mut self, a: Signal, b: Signal, carry: Signal) -> (Signal, Signal) {
+        let half = self.xor(a, b);
+        let sum = self.xor(half, carry);
+
+        let both = self.and(a, b);
+        let propagated = self.and(half, carry);
+        (sum, self.or(both, propagated))
+    }
+
+    /// Ripple carry adder, whose result is one bit wider than the wider operand.
+    pub fn bv_add(&mut self, a: &BitVec, b: &BitVec) -> BitVec {
+        let width = a.width().max(b.width());
+        let mut carry = Signal::FALSE;
+        let mut sum = Vec::with_capacity(width + 1);
+        for idx in 0..width {
+            let (bit, next_carry) = self.full_adder(a.bit(idx), b.bit(idx), carry);
+            sum.push(bit);
+            carry = next_carry;
+        }
+        sum.push(carry);
+        BitVec::from_signals(sum)
+    }
+
+    /// Shift-and-add multiplier, whose result is as wide as both operands together.
+    pub fn bv_mul(&mut self, a: &BitVec, b: &BitVec) -> BitVec {
+        let width = a.width() + b.width();
+        let mut product = BitVec::constant(0, width);
+        for (shift, &b_bit) in b.bits().iter().enumerate() {
+            let partial: Vec<Signal> = (0..shift)
+                .map(|_| Signal::FALSE)
+                .chain(a.bits().iter().map(|&a_bit| self.and(a_bit, b_bit)))
+                .collect();
+            product = self
+                .bv_add(&product, &BitVec::from_signals(partial))
+                .truncate(width);
+        }
+        product
+    }
+
+    pub fn bv_eq(&mut self, a: &BitVec, b: &BitVec) -> Signal {
+        let width = a.width().max(b.width());
+        let equal: Vec<Signal> = (0..width)
+            .map(|idx| self.eq(a.bit(idx), b.bit(idx)))
+            .collect();
+        self.and_all(equal)
+    }
+
+    /// Unsigned `a < b`.
+    pub fn bv_ult(&mut self, a: &BitVec, b: &BitVec) -> Signal {
+        // Compare from the least significant bit, more significant bits decide.
+        let mut less = Signal::FALSE;
+        for idx in 0..a.width().max(b.width()) {
+            let (a_bit, b_bit) = (a.bit(idx), b.bit(idx));
+            let bit_less = self.and(!a_bit, b_bit);
+            let bit_equal = self.eq(a_bit, b_bit);
+            let lower_less = self.and(bit_equal, less);
+            less = self.or(bit_less, lower_less);
+        }
+        less
+    }
+
+    /// Unsigned `a <= b`.
+    pub fn bv_ule(&mut self, a: &BitVec, b: &BitVec) -> Signal {
+        !self.bv_ult(b, a)
+    }
+
+    /// Unsigned `a > b`.
+    pub fn bv_ugt(&mut self, a: &BitVec, b: &BitVec) -> Signal {
+        self.bv_ult(b, a)
+    }
+
+    /// Unsigned `a >= b`.
+    pub fn bv_uge(&mut self, a: &BitVec, b: &BitVec) -> Signal {
+        !self.bv_ult(a, b)
+    }
+
+    /// `then` if `cond` is true, otherwise `otherwise`.
+    pub fn bv_ite(&mut self, cond: Signal, then: &BitVec, otherwise: &BitVec) -> BitVec {
+        let width = then.width().max(otherwise.width());
+        let bits = (0..width)
+            .map(|idx| self.ite(cond, then.bit(idx), otherwise.bit(idx)))
+            .collect();
+        BitVec::from_signals(bits)
+    }
+
+    /// Shift left by a constant, keeping the width.
+    pub fn bv_shl(&mut self, a: &BitVec, shift: usize) -> BitVec {
+        let bits = (0..a.width())
+            .map(|idx| match idx.checked_sub(shift) {
+                Some(source) => a.bit(source),
+                None => Signal::FALSE,
+            })
+            .collect();
+        BitVec::from_signals(bits)
+    }
+
+    /// Logical shift right by a constant, keeping the width.
+    pub fn bv_lshr(&mut self, a: &BitVec, shift: usize) -> BitVec {
+        let bits = (0..a.width())
+            .map(|idx| a.bit(idx.saturating_add(shift)))
+            .collect();
+        BitVec::from_signals(bits)
+    }
+
+    /// Shift left by the value of `shift` with a barrel shifter, keeping the width.
+    pub fn bv_shl_by(&mut self, a: &BitVec, shift: &BitVec) -> BitVec {
+        self.barrel_shift(a, shift, Self::bv_shl)
+    }
+
+    /// Logical shift right by the value of `shift` with a barrel shifter, keeping the width.
+    pub fn bv_lshr_by(&mut self, a: &BitVec, shift: &BitVec) -> BitVec {
+        self.barrel_shift(a, shift, Self::bv_lshr)
+    }
+
+    fn barrel_shift(
+        &mut self,
+        a: &BitVec,
+        shift: &BitVec,
+        shift_by: fn(&mut Self, &BitVec, usize) -> BitVec,
+    ) -> BitVec {
+        let mut result = a.clone();
+        for (idx, &bit) in shift.bits().iter().enumerate() {
+            // Shifting by at least the width clears all bits.
+            let shifted = match 1usize.checked_shl(idx as u32) {
+                Some(amount) if amount < a.width() => shift_by(self, &result, amount),
+                _ => BitVec::constant(0, a.width()),
+            };
+            result = self.bv_ite(bit, &shifted, &result);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{circuit::Circuit, Solver};
+
+    use super::BitVec;
+
+    #[test]
+    fn constant_operations() {
+        let mut solver = Solver::new();
+        let mut circuit = Circuit::new();
+        let (a, b) = (BitVec::constant(13, 4), BitVec::constant(6, 3));
+
+        let sum = circuit.bv_add(&a, &b);
+        let product = circuit.bv_mul(&a, &b);
+        let shifted = circuit.bv_shl(&a, 2);
+        let less = circuit.bv_ult(&b, &a);
+        let equal = circuit.bv_eq(&a, &BitVec::constant(13, 8));
+        let input = circuit.new_input(&mut solver);
+        circuit.assert(&mut solver, input);
+
+        let model = solver.solve().unwrap_sat();
+        assert_eq!(sum.value(&circuit, &model), 19);
+        assert_eq!(product.value(&circuit, &model), 78);
+        assert_eq!(shifted.value(&circuit, &model), 4);
+        assert!(circuit.value(less, &model));
+        assert!(circuit.value(equal, &model));
+    }
+}
@@ -0,0 +1,169 @@
+//! Bounded model checking of AIGER circuits.
+//!
+//! The transition relation is unrolled one step at a time into a single incremental solver.
+//! The bad states of each depth are checked under an assumption, which is refuted afterwards.
+
+use crate::{
+    aiger::{Aig, Frame},
+    circuit::{Circuit, Signal},
+    Model, Solver,
+};
+
+/// Execution of the circuit, which reaches a bad state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    /// Index of the violated property.
+    pub property: usize,
+    /// Initial values of the latches.
+    pub initial: Vec<bool>,
+    /// Input values of each step. The bad state is reached in the last step.
+    pub inputs: Vec<Vec<bool>>,
+}
+
+impl Trace {
+    /// Witness in the format of the hardware model checking competition.
+    pub fn to_witness(&self) -> String {
+        let bits = |values: &[bool]| -> String {
+            values
+                .iter()
+                .map(|&value| if value { '1' } else { '0' })
+                .collect()
+        };
+
+        let mut witness = format!("1\nb{}\n{}\n", self.property, bits(&self.initial));
+        for inputs in &self.inputs {
+            witness.push_str(&bits(inputs));
+            witness.push('\n');
+        }
+        witness.push_str(".\n");
+        witness
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BmcResult {
+    Counterexample(Trace),
+    /// No bad state is reachable within the checked depth.
+    NoCounterexample,
+    /// The solver was interrupted.
+    Unknown,
+}
+
+pub struct Bmc<'a> {
+    aig: &'a Aig,
+    solver: Solver,
+    circuit: Circuit,
+    frames: Vec<Frame>,
+}
+
+impl<'a> Bmc<'a> {
+    pub fn new(aig: &'a Aig) -> Self {
+        let mut solver = Solver::new();
+        let mut circuit = Circuit::new();
+        let initial = aig
+            .latches
+            .iter()
+            .map(|latch| match latch.reset {
+                0 => Signal::FALSE,
+                1 => Signal::TRUE,
+                _ => circuit.new_input(&mut solver),
+            })
+            .collect();
+        let frame = Self::frame(aig, &mut solver, &mut circuit, initial);
+
+        Self {
+            aig,
+            solver,
+            circuit,
+            frames: vec![frame],
+        }
+    }
+
+    fn frame(aig: &Aig, solver: &mut Solver, circuit: &mut Circuit, latches: Vec<Signal>) -> Frame {
+        let inputs = aig
+            .inputs
+            .iter()
+            .map(|_| circuit.new_input(solver))
+            .collect();
+        let frame = aig.frame(circuit, inputs, latches);
+        for &constraint in &frame.constraints {
+            circuit.assert(solver, constraint);
+        }
+        frame
+    }
+
+    /// Bad state signals. Without bad state properties, the outputs are the properties.
+    fn properties(&self, frame: &Frame) -> Vec<Signal> {
+        if self.aig.bad.is_empty() {
+            frame.outputs.clone()
+        } else {
+            frame.bad.clone()
+        }
+    }
+
+    /// Number of transitions of the next check. All smaller depths are free of bad states.
+    pub fn depth(&self) -> usize {
+        self.frames.len() - 1
+    }
+
+    /// The incremental solver, e.g. to set a budget.
+    pub fn solver_mut(&mut self) -> &mut Solver {
+        &mut self.solver
+    }
+
+    /// Check, whether a bad state is reachable with `depth()` transitions. If not, the
+    /// transition relation is unrolled one more step.
+    pub fn step(&mut self) -> BmcResult {
+        let frame = self.frames.last().unwrap().clone();
+        let properties = self.properties(&frame);
+        let any_bad = self.circuit.or_all(properties.iter().copied());
+
+        if any_bad != Signal::FALSE {
+            let activation = self.circuit.encode(&mut self.solver, any_bad);
+            match self.solver.solve_with_assumptions(&[activation]) {
+                crate::Result::Sat(model) => {
+                    return BmcResult::Counterexample(self.trace(&model, &properties))
+                }
+                crate::Result::Unsat(_) => self.solver.add_clause([-activation]),
+                crate::Result::Unknown => return BmcResult::Unknown,
+            }
+        }
+
+        let frame = Self::frame(self.aig, &mut self.solver, &mut self.circuit, frame.next);
+        self.frames.push(frame);
+        BmcResult::NoCounterexample
+    }
+
+    /// Search a counterexample with at most `max_depth` transitions.
+    pub fn check(&mut self, max_depth: usize) -> BmcResult {
+        while self.depth() <= max_depth {
+            let result = self.step();
+            if result != BmcResult::NoCounterexample {
+                return result;
+            }
+        }
+        BmcResult::NoCounterexample
+    }
+
+    fn trace(&self, model: &Model, properties: &[Signal]) -> Trace {
+        let values = |signals: &[Signal]| {
+            signals
+                .iter()
+                .map(|&s| self.circuit.value(s, model))
+                .collect()
+        };
+
+        Trace {
+            property: properties
+                .iter()
+                .position(|&property| self.circuit.value(property, model))
+                .unwrap(),
+            initial: values(&self.frames[0].latches),
+            inputs: self
+                .frames
+                .iter()
+                .map(|frame| values(&frame.inputs))
+                .collect(),
+        }
+    }
+}
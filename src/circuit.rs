@@ -0,0 +1,337 @@
+//! And-inverter graphs, which are translated to CNF.
+//!
+//! Gates are built with the methods of [`Circuit`], which simplifies them and shares structurally
+//! equal and-gates. The clauses for a signal are only emitted, when it is encoded or asserted.
+
+use std::{collections::HashMap, ops::Not};
+
+use crate::{encodings::ClauseSink, Model};
+
+/// Output of a node of a [`Circuit`], which may be negated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Signal(u32);
+
+impl Signal {
+    pub const FALSE: Signal = Signal(0);
+    pub const TRUE: Signal = Signal(1);
+
+    fn new(node: usize, negated: bool) -> Self {
+        Self((node as u32) << 1 | negated as u32)
+    }
+
+    fn node(self) -> usize {
+        (self.0 >> 1) as usize
+    }
+
+    pub fn is_negated(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    pub fn is_constant(self) -> bool {
+        self.node() == 0
+    }
+}
+
+impl Not for Signal {
+    type Output = Signal;
+
+    fn not(self) -> Self::Output {
+        Signal(self.0 ^ 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    False,
+    /// Variable of the solver.
+    Input(i32),
+    And(Signal, Signal),
+}
+
+/// How gates are translated to clauses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GateEncoding {
+    /// Every gate variable is equivalent to its gate.
+    #[default]
+    Tseitin,
+    /// Only the implications required by the polarities, in which a gate is used, are encoded.
+    /// Gate variables of encoded signals may be true, even if the gate is false and vice versa.
+    PlaistedGreenbaum,
+}
+
+const POSITIVE: u8 = 1;
+const NEGATIVE: u8 = 2;
+
+/// And-inverter graph with structural hashing.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    nodes: Vec<Node>,
+    ands: HashMap<(Signal, Signal), usize>,
+    inputs: HashMap<i32, usize>,
+    encoding: GateEncoding,
+
+    /// Solver variable of each node, allocated when the node is encoded.
+    vars: Vec<Option<i32>>,
+    /// Polarities, in which the clauses of a node were emitted.
+    polarities: Vec<u8>,
+}
+
+impl Default for Circuit {
+    fn default() -> Self {
+        Self::with_encoding(GateEncoding::default())
+    }
+}
+
+impl Circuit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_encoding(encoding: GateEncoding) -> Self {
+        Self {
+            nodes: vec![Node::False],
+            ands: HashMap::new(),
+            inputs: HashMap::new(),
+            encoding,
+            vars: vec![None],
+            polarities: vec![0],
+        }
+    }
+
+    /// Number of nodes including inputs and the constant.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() == 1
+    }
+
+    fn push(&mut self, node: Node) -> usize {
+        self.nodes.push(node);
+        self.vars.push(None);
+        self.polarities.push(0);
+        self.nodes.len() - 1
+    }
+
+    /// Signal of the solver literal `lit`.
+    pub fn lit(&mut self, lit: i32) -> Signal {
+        assert_ne!(lit, 0, "0 is not a literal");
+
+        let var = lit.abs();
+        let node = match self.inputs.get(&var) {
+            Some(&node) => node,
+            None => {
+                let node = self.push(Node::Input(var));
+                self.vars[node] = Some(var);
+                self.inputs.insert(var, node);
+                node
+            }
+        };
+        Signal::new(node, lit < 0)
+    }
+
+    /// Signal of a fresh variable allocated by `sink`.
+    pub fn new_input(&mut self, sink: &mut impl ClauseSink) -> Signal {
+        let var = sink.new_var();
+        self.lit(var)
+    }
+
+    pub fn constant(&self, value: bool) -> Signal {
+        if value {
+            Signal::TRUE
+        } else {
+            Signal::FALSE
+        }
+    }
+
+    pub fn not(&self, a: Signal) -> Signal {
+        !a
+    }
+
+    pub fn and(&mut self, a: Signal, b: Signal) -> Signal {
+        let (a, b) = (a.min(b), a.max(b));
+        if a == Signal::FALSE || a == !b {
+            return Signal::FALSE;
+        }
+        if a == Signal::TRUE || a == b {
+            return b;
+        }
+
+        if let Some(&node) = self.ands.get(&(a, b)) {
+            return Signal::new(node, false);
+        }
+        let node = self.push(Node::And(a, b));
+        self.ands.insert((a, b), node);
+        Signal::new(node, false)
+    }
+
+    pub fn or(&mut self, a: Signal, b: Signal) -> Signal {
+        !self.and(!a, !b)
+    }
+
+    pub fn xor(&mut self, a: Signal, b: Signal) -> Signal {
+        let only_a = self.and(a, !b);
+        let only_b = self.and(!a, b);
+        self.or(only_a, only_b)
+    }
+
+    pub fn eq(&mut self, a: Signal, b: Signal) -> Signal {
+        !self.xor(a, b)
+    }
+
+    pub fn implies(&mut self, a: Signal, b: Signal) -> Signal {
+        self.or(!a, b)
+    }
+
+    /// If-then-else: `then` if `cond` is true, otherwise `otherwise`.
+    pub fn ite(&mut self, cond: Signal, then: Signal, otherwise: Signal) -> Signal {
+        if then == otherwise {
+            return then;
+        }
+        let then = self.and(cond, then);
+        let otherwise = self.and(!cond, otherwise);
+        self.or(then, otherwise)
+    }
+
+    pub fn and_all(&mut self, signals: impl IntoIterator<Item = Signal>) -> Signal {
+        signals
+            .into_iter()
+            .fold(Signal::TRUE, |acc, signal| self.and(acc, signal))
+    }
+
+    pub fn or_all(&mut self, signals: impl IntoIterator<Item = Signal>) -> Signal {
+        signals
+            .into_iter()
+            .fold(Signal::FALSE, |acc, signal| self.or(acc, signal))
+    }
+
+    pub fn xor_all(&mut self, signals: impl IntoIterator<Item = Signal>) -> Signal {
+        signals
+            .into_iter()
+            .fold(Signal::FALSE, |acc, signal| self.xor(acc, signal))
+    }
+
+    /// Value of `signal` under the assignment of the inputs in `model`.
+    /// Inputs, which are not part of the model, are false.
+    pub fn value(&self, signal: Signal, model: &Model) -> bool {
+        let mut values = vec![false; signal.node() + 1];
+        for node in 1..values.len() {
+            values[node] = match self.nodes[node] {
+                Node::False => false,
+                Node::Input(var) => model.lit(var),
+                Node::And(a, b) => {
+                    (values[a.node()] != a.is_negated()) && (values[b.node()] != b.is_negated())
+                }
+            };
+        }
+        values[signal.node()] != signal.is_negated()
+    }
+
+    /// Emit the clauses for `signal` into `sink` and return a solver literal for it.
+    /// With [`GateEncoding::PlaistedGreenbaum`] the literal may be used in both polarities.
+    pub fn encode(&mut self, sink: &mut impl ClauseSink, signal: Signal) -> i32 {
+        self.emit(sink, signal, POSITIVE | NEGATIVE);
+        self.lit_of(sink, signal)
+    }
+
+    /// Add the constraint, that `signal` is true.
+    pub fn assert(&mut self, sink: &mut impl ClauseSink, signal: Signal) {
+        if signal == Signal::TRUE {
+            return;
+        }
+        if signal == Signal::FALSE {
+            sink.add_clause(&[]);
+            return;
+        }
+
+        self.emit(sink, signal, POSITIVE);
+        let lit = self.lit_of(sink, signal);
+        sink.add_clause(&[lit]);
+    }
+
+    /// Solver literal of `signal`, which allocates a variable for its node, if necessary.
+    fn lit_of(&mut self, sink: &mut impl ClauseSink, signal: Signal) -> i32 {
+        let node = signal.node();
+        let var = match self.vars[node] {
+            Some(var) => var,
+            None => {
+                let var = sink.new_var();
+                self.vars[node] = Some(var);
+                if let Node::False = self.nodes[node] {
+                    sink.add_clause(&[-var]);
+                }
+                var
+            }
+        };
+
+        if signal.is_negated() {
+            -var
+        } else {
+            var
+        }
+    }
+
+    /// Emit the clauses of the cone of `signal`, which are required for the given polarities.
+    fn emit(&mut self, sink: &mut impl ClauseSink, signal: Signal, polarities: u8) {
+        let flip = |polarities: u8, negated: bool| {
+            if negated {
+                (polarities & POSITIVE) << 1 | (polarities & NEGATIVE) >> 1
+            } else {
+                polarities
+            }
+        };
+        let polarities = match self.encoding {
+            GateEncoding::Tseitin => POSITIVE | NEGATIVE,
+            GateEncoding::PlaistedGreenbaum => polarities,
+        };
+
+        let mut stack = vec![(signal.node(), flip(polarities, signal.is_negated()))];
+        while let Some((node, polarities)) = stack.pop() {
+            let Node::And(a, b) = self.nodes[node] else {
+                continue;
+            };
+            let missing = polarities & !self.polarities[node];
+            if missing == 0 {
+                continue;
+            }
+            self.polarities[node] |= missing;
+
+            let gate = self.lit_of(sink, Signal::new(node, false));
+            let (lit_a, lit_b) = (self.lit_of(sink, a), self.lit_of(sink, b));
+            if missing & POSITIVE != 0 {
+                sink.add_clause(&[-gate, lit_a]);
+                sink.add_clause(&[-gate, lit_b]);
+            }
+            if missing & NEGATIVE != 0 {
+                sink.add_clause(&[gate, -lit_a, -lit_b]);
+            }
+
+            stack.push((a.node(), flip(missing, a.is_negated())));
+            stack.push((b.node(), flip(missing, b.is_negated())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Circuit, Signal};
+
+    #[test]
+    fn structural_hashing() {
+        let mut circuit = Circuit::new();
+        let a = circuit.lit(1);
+        let b = circuit.lit(-2);
+
+        let first = circuit.and(a, b);
+        let second = circuit.and(b, a);
+        assert_eq!(first, second);
+        assert_eq!(circuit.len(), 4);
+
+        assert_eq!(circuit.and(a, !a), Signal::FALSE);
+        assert_eq!(circuit.and(a, Signal::TRUE), a);
+        assert_eq!(circuit.or(a, Signal::TRUE), Signal::TRUE);
+        assert_eq!(circuit.xor(a, a), Signal::FALSE);
+        assert_eq!(circuit.lit(2), !b);
+    }
+}
@@ -1,21 +1,63 @@
 use std::error::Error;
 
+/// Parser for the DIMACS CNF format.
 pub struct Dimacs;
 
+/// A constraint of the XOR-CNF extension of the DIMACS format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constraint {
+    Clause(Vec<i32>),
+    /// Lines starting with `x`, an odd number of the literals has to be true.
+    Xor(Vec<i32>),
+}
+
+/// A line of the incremental iCNF format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Icnf {
+    Clause(Vec<i32>),
+    /// Lines starting with `a`, the formula is solved under these assumptions.
+    Assumptions(Vec<i32>),
+}
+
 impl Dimacs {
+    /// Parse the clauses of a DIMACS CNF file. Clause `i` is the `i`-th clause in the file.
     pub fn parse(input: &str) -> Result<Vec<Vec<i32>>, Box<dyn Error>> {
+        Self::lines(input).map(Self::parse_lits).collect()
+    }
+
+    /// Parse clauses and xor constraints (`x 1 -2 3 0`).
+    pub fn parse_constraints(input: &str) -> Result<Vec<Constraint>, Box<dyn Error>> {
+        Self::lines(input)
+            .map(|line| match line.strip_prefix('x') {
+                Some(xor) => Self::parse_lits(xor).map(Constraint::Xor),
+                None => Self::parse_lits(line).map(Constraint::Clause),
+            })
+            .collect()
+    }
+
+    /// Parse clauses and assumption lines (`a 1 -2 0`) in the order of the input.
+    pub fn parse_icnf(input: &str) -> Result<Vec<Icnf>, Box<dyn Error>> {
+        Self::lines(input)
+            .map(|line| match line.strip_prefix('a') {
+                Some(assumptions) => Self::parse_lits(assumptions).map(Icnf::Assumptions),
+                None => Self::parse_lits(line).map(Icnf::Clause),
+            })
+            .collect()
+    }
+
+    fn lines(input: &str) -> impl Iterator<Item = &str> {
         input
             .lines()
             .map(str::trim_start)
             .filter(|line| !line.is_empty())
             .filter(|line| !line.starts_with('c'))
             .filter(|line| !line.starts_with('p'))
-            .map(|line| -> Result<_, Box<dyn Error>> {
-                line.split_whitespace()
-                    .map(|n| n.parse::<i32>().map_err(|e| Box::new(e) as Box<dyn Error>))
-                    .filter(|n| !matches!(n, Ok(0)))
-                    .collect::<Result<Vec<_>, _>>()
-            })
+    }
+
+    fn parse_lits(line: &str) -> Result<Vec<i32>, Box<dyn Error>> {
+        line.split_whitespace()
+            .map(|n| n.parse::<i32>().map_err(|e| Box::new(e) as Box<dyn Error>))
+            .filter(|n| !matches!(n, Ok(0)))
             .collect::<Result<Vec<_>, _>>()
     }
 }
@@ -41,4 +83,46 @@ mod tests {
         assert_eq!(result[2], vec![1]);
         assert_eq!(result[3], vec![2]);
     }
+
+    #[test]
+    fn parse_xor_test() {
+        let dimacs = "
+        p cnf 3 3  \n\
+        1 -2 0     \n\
+        x 1 -2 3 0 \n\
+        x2 3 0
+        ";
+
+        let result = Dimacs::parse_constraints(dimacs).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                Constraint::Clause(vec![1, -2]),
+                Constraint::Xor(vec![1, -2, 3]),
+                Constraint::Xor(vec![2, 3]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_icnf_test() {
+        let icnf = "
+        p inccnf   \n\
+        1 -2 0     \n\
+        a -1 0     \n\
+        2 3 0      \n\
+        a 0
+        ";
+
+        let result = Dimacs::parse_icnf(icnf).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                Icnf::Clause(vec![1, -2]),
+                Icnf::Assumptions(vec![-1]),
+                Icnf::Clause(vec![2, 3]),
+                Icnf::Assumptions(vec![]),
+            ]
+        );
+    }
 }
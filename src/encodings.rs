@@ -0,0 +1,569 @@
+//! CNF encodings of cardinality constraints.
+//!
+//! The encodings write their clauses into a [`ClauseSink`], which also allocates the auxiliary
+//! variables. A [`Solver`] is a sink itself, otherwise clauses can be collected in a [`Cnf`].
+//! All encodings expect the literals to be over distinct variables.
+
+use std::collections::BTreeMap;
+
+use crate::Solver;
+
+/// Destination of encoded clauses, which also allocates auxiliary variables.
+pub trait ClauseSink {
+    fn new_var(&mut self) -> i32;
+
+    fn add_clause(&mut self, clause: &[i32]);
+}
+
+impl ClauseSink for Solver {
+    fn new_var(&mut self) -> i32 {
+        Solver::new_var(self)
+    }
+
+    fn add_clause(&mut self, clause: &[i32]) {
+        Solver::add_clause(self, clause.iter().copied())
+    }
+}
+
+/// Allocates auxiliary variables after the largest variable in use.
+#[derive(Debug, Clone, Default)]
+pub struct VarAllocator {
+    num_vars: usize,
+}
+
+impl VarAllocator {
+    pub fn new(num_vars: usize) -> Self {
+        Self { num_vars }
+    }
+
+    /// Allocate variables after the variables known to `solver`.
+    pub fn for_solver(solver: &Solver) -> Self {
+        Self::new(solver.num_vars())
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn new_var(&mut self) -> i32 {
+        self.num_vars += 1;
+        self.num_vars as i32
+    }
+
+    /// Make sure, that the variable of `lit` is never allocated.
+    pub fn reserve(&mut self, lit: i32) {
+        self.num_vars = self.num_vars.max(lit.unsigned_abs() as usize);
+    }
+}
+
+/// Clauses collected in memory.
+#[derive(Debug, Clone, Default)]
+pub struct Cnf {
+    pub vars: VarAllocator,
+    pub clauses: Vec<Vec<i32>>,
+}
+
+impl Cnf {
+    pub fn new(vars: VarAllocator) -> Self {
+        Self {
+            vars,
+            clauses: Vec::new(),
+        }
+    }
+
+    /// Add the clauses to `solver`. The variables of the allocator are reserved in the solver, so
+    /// that later calls to [`Solver::new_var`] don't clash with them.
+    pub fn add_to(&self, solver: &mut Solver) {
+        while solver.num_vars() < self.vars.num_vars() {
+            solver.new_var();
+        }
+        for clause in &self.clauses {
+            solver.add_clause(clause.iter().copied());
+        }
+    }
+}
+
+impl ClauseSink for Cnf {
+    fn new_var(&mut self) -> i32 {
+        self.vars.new_var()
+    }
+
+    fn add_clause(&mut self, clause: &[i32]) {
+        for &lit in clause {
+            self.vars.reserve(lit);
+        }
+        self.clauses.push(clause.to_vec());
+    }
+}
+
+/// Encodings for at-most-one constraints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AtMostOneEncoding {
+    /// Binary clause for every pair of literals.
+    Pairwise,
+    /// Sinz's sequential counter.
+    #[default]
+    SequentialCounter,
+    /// Klieber and Kwon's commander encoding with groups of three.
+    Commander,
+    /// Chen's two dimensional product encoding.
+    Product,
+}
+
+/// Encodings for at-most-k constraints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AtMostKEncoding {
+    /// Sinz's sequential counter.
+    SequentialCounter,
+    /// Bailleux and Boufkhad's totalizer.
+    #[default]
+    Totalizer,
+    /// Totalizer, which counts modulo `⌈√(k + 1)⌉` to reduce the number of clauses.
+    ModuloTotalizer,
+    /// Odd-even merge sorting network, which only keeps the largest `k + 1` outputs.
+    CardinalityNetwork,
+}
+
+/// At most one of the literals is true.
+pub fn at_most_one(sink: &mut impl ClauseSink, lits: &[i32], encoding: AtMostOneEncoding) {
+    if lits.len() <= 1 {
+        return;
+    }
+
+    match encoding {
+        AtMostOneEncoding::Pairwise => pairwise(sink, lits),
+        AtMostOneEncoding::SequentialCounter => sequential_counter(sink, lits, 1),
+        AtMostOneEncoding::Commander => commander(sink, lits),
+        AtMostOneEncoding::Product => product(sink, lits),
+    }
+}
+
+/// Exactly one of the literals is true.
+pub fn exactly_one(sink: &mut impl ClauseSink, lits: &[i32], encoding: AtMostOneEncoding) {
+    sink.add_clause(lits);
+    at_most_one(sink, lits, encoding);
+}
+
+/// At most `k` of the literals are true.
+pub fn at_most_k(sink: &mut impl ClauseSink, lits: &[i32], k: usize, encoding: AtMostKEncoding) {
+    if k >= lits.len() {
+        return;
+    }
+    if k == 0 {
+        for &lit in lits {
+            sink.add_clause(&[-lit]);
+        }
+        return;
+    }
+
+    match encoding {
+        AtMostKEncoding::SequentialCounter => sequential_counter(sink, lits, k),
+        AtMostKEncoding::Totalizer => totalizer(sink, lits, k),
+        AtMostKEncoding::ModuloTotalizer => modulo_totalizer(sink, lits, k),
+        AtMostKEncoding::CardinalityNetwork => cardinality_network(sink, lits, k),
+    }
+}
+
+/// At least `k` of the literals are true.
+pub fn at_least_k(sink: &mut impl ClauseSink, lits: &[i32], k: usize, encoding: AtMostKEncoding) {
+    if k > lits.len() {
+        sink.add_clause(&[]);
+        return;
+    }
+
+    // At least k literals are true, iff at most n - k negated literals are true.
+    let negated: Vec<i32> = lits.iter().map(|&lit| -lit).collect();
+    at_most_k(sink, &negated, lits.len() - k, encoding);
+}
+
+/// Exactly `k` of the literals are true.
+pub fn exactly_k(sink: &mut impl ClauseSink, lits: &[i32], k: usize, encoding: AtMostKEncoding) {
+    at_most_k(sink, lits, k, encoding);
+    at_least_k(sink, lits, k, encoding);
+}
+
+fn pairwise(sink: &mut impl ClauseSink, lits: &[i32]) {
+    for (idx, &first) in lits.iter().enumerate() {
+        for &second in &lits[idx + 1..] {
+            sink.add_clause(&[-first, -second]);
+        }
+    }
+}
+
+/// `registers[i][j]` is implied, if at least `j + 1` of the first `i + 1` literals are true.
+fn sequential_counter(sink: &mut impl ClauseSink, lits: &[i32], k: usize) {
+    let n = lits.len();
+    let registers: Vec<Vec<i32>> = (0..n - 1)
+        .map(|_| (0..k).map(|_| sink.new_var()).collect())
+        .collect();
+
+    sink.add_clause(&[-lits[0], registers[0][0]]);
+    for &register in &registers[0][1..] {
+        sink.add_clause(&[-register]);
+    }
+
+    for idx in 1..n - 1 {
+        let (lit, previous, current) = (lits[idx], &registers[idx - 1], &registers[idx]);
+
+        sink.add_clause(&[-lit, current[0]]);
+        for j in 0..k {
+            sink.add_clause(&[-previous[j], current[j]]);
+        }
+        for j in 1..k {
+            sink.add_clause(&[-lit, -previous[j - 1], current[j]]);
+        }
+        sink.add_clause(&[-lit, -previous[k - 1]]);
+    }
+
+    sink.add_clause(&[-lits[n - 1], -registers[n - 2][k - 1]]);
+}
+
+fn commander(sink: &mut impl ClauseSink, lits: &[i32]) {
+    const GROUP_SIZE: usize = 3;
+
+    if lits.len() <= 2 * GROUP_SIZE {
+        pairwise(sink, lits);
+        return;
+    }
+
+    let mut commanders = Vec::new();
+    for group in lits.chunks(GROUP_SIZE) {
+        let commander = sink.new_var();
+        commanders.push(commander);
+
+        pairwise(sink, group);
+
+        // The commander is true, iff a literal of its group is true.
+        let mut clause = vec![-commander];
+        clause.extend_from_slice(group);
+        sink.add_clause(&clause);
+        for &lit in group {
+            sink.add_clause(&[-lit, commander]);
+        }
+    }
+
+    commander(sink, &commanders);
+}
+
+fn product(sink: &mut impl ClauseSink, lits: &[i32]) {
+    if lits.len() <= 4 {
+        pairwise(sink, lits);
+        return;
+    }
+
+    let rows_len = (lits.len() as f64).sqrt().ceil() as usize;
+    let columns_len = lits.len().div_ceil(rows_len);
+    let rows: Vec<i32> = (0..rows_len).map(|_| sink.new_var()).collect();
+    let columns: Vec<i32> = (0..columns_len).map(|_| sink.new_var()).collect();
+
+    // Two true literals differ in their row or their column.
+    for (idx, &lit) in lits.iter().enumerate() {
+        sink.add_clause(&[-lit, rows[idx / columns_len]]);
+        sink.add_clause(&[-lit, columns[idx % columns_len]]);
+    }
+
+    product(sink, &rows);
+    product(sink, &columns);
+}
+
+fn totalizer(sink: &mut impl ClauseSink, lits: &[i32], k: usize) {
+    let outputs = totalizer_node(sink, lits, k + 1);
+    if let Some(&exceeded) = outputs.get(k) {
+        sink.add_clause(&[-exceeded]);
+    }
+}
+
+/// Outputs of a totalizer over `lits`. `outputs[i]` is implied, if at least `i + 1` of the literals
+/// are true, so assuming the negated output bounds the number of true literals.
+pub fn totalizer_outputs(sink: &mut impl ClauseSink, lits: &[i32]) -> Vec<i32> {
+    if lits.is_empty() {
+        return Vec::new();
+    }
+    totalizer_node(sink, lits, lits.len())
+}
+
+/// Unary representation of the number of true literals, saturated at `saturation`.
+/// `outputs[i]` is implied, if at least `i + 1` literals are true.
+fn totalizer_node(sink: &mut impl ClauseSink, lits: &[i32], saturation: usize) -> Vec<i32> {
+    let terms: Vec<(u64, i32)> = lits.iter().map(|&lit| (1, lit)).collect();
+    // With unit weights every sum up to the saturation occurs, so the outputs are consecutive.
+    weighted_totalizer(sink, &terms, saturation as u64)
+        .into_values()
+        .collect()
+}
+
+/// Outputs of the generalized totalizer for `terms`, indexed by the sums, which are
+/// saturated at `saturation`. An output is implied, if the sum of the terms reaches its value.
+pub(crate) fn weighted_totalizer(
+    sink: &mut impl ClauseSink,
+    terms: &[(u64, i32)],
+    saturation: u64,
+) -> BTreeMap<u64, i32> {
+    match terms {
+        [] => return BTreeMap::new(),
+        [(weight, lit)] => return BTreeMap::from([((*weight).min(saturation), *lit)]),
+        _ => {}
+    }
+
+    let (first, second) = terms.split_at(terms.len() / 2);
+    let first = weighted_totalizer(sink, first, saturation);
+    let second = weighted_totalizer(sink, second, saturation);
+
+    let mut outputs = BTreeMap::new();
+    let first_sums = first.iter().map(|(&sum, &lit)| (sum, Some(lit)));
+    for (first_sum, first_lit) in std::iter::once((0, None)).chain(first_sums) {
+        let second_sums = second.iter().map(|(&sum, &lit)| (sum, Some(lit)));
+        for (second_sum, second_lit) in std::iter::once((0, None)).chain(second_sums) {
+            let sum = (first_sum + second_sum).min(saturation);
+            if sum == 0 {
+                continue;
+            }
+
+            let output = *outputs.entry(sum).or_insert_with(|| sink.new_var());
+            match (first_lit, second_lit) {
+                (Some(first), Some(second)) => sink.add_clause(&[-first, -second, output]),
+                (Some(lit), None) | (None, Some(lit)) => sink.add_clause(&[-lit, output]),
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    outputs
+}
+
+/// Number of true literals represented as `upper * modulus + lower`.
+/// `lower[i]` is implied, if the lower digit is at least `i + 1` and `upper[i]` is implied, if
+/// the upper digit is at least `i + 1`, unless the upper digit is overestimated.
+struct ModuloNode {
+    lower: Vec<i32>,
+    upper: Vec<i32>,
+}
+
+fn modulo_totalizer(sink: &mut impl ClauseSink, lits: &[i32], k: usize) {
+    let modulus = ((k + 1) as f64).sqrt().ceil().max(2.0) as usize;
+    let max_upper = k / modulus + 1;
+
+    let root = modulo_totalizer_node(sink, lits, modulus, max_upper);
+
+    // Forbid all represented values larger than k.
+    for upper in 0..=root.upper.len() {
+        for lower in 0..=root.lower.len() {
+            if upper * modulus + lower <= k {
+                continue;
+            }
+
+            let mut clause = Vec::with_capacity(2);
+            if upper > 0 {
+                clause.push(-root.upper[upper - 1]);
+            }
+            if lower > 0 {
+                clause.push(-root.lower[lower - 1]);
+            }
+            sink.add_clause(&clause);
+        }
+    }
+}
+
+fn modulo_totalizer_node(
+    sink: &mut impl ClauseSink,
+    lits: &[i32],
+    modulus: usize,
+    max_upper: usize,
+) -> ModuloNode {
+    if let [lit] = lits {
+        return ModuloNode {
+            lower: vec![*lit],
+            upper: Vec::new(),
+        };
+    }
+
+    let (first, second) = lits.split_at(lits.len() / 2);
+    let first = modulo_totalizer_node(sink, first, modulus, max_upper);
+    let second = modulo_totalizer_node(sink, second, modulus, max_upper);
+
+    let lower: Vec<i32> = (0..lits.len().min(modulus - 1))
+        .map(|_| sink.new_var())
+        .collect();
+    let upper: Vec<i32> = (0..(lits.len() / modulus).min(max_upper))
+        .map(|_| sink.new_var())
+        .collect();
+    let carry = (first.lower.len() + second.lower.len() >= modulus).then(|| sink.new_var());
+
+    let premise = |digits: &[i32], idx: usize| (idx > 0).then(|| -digits[idx - 1]);
+
+    for i in 0..=first.lower.len() {
+        for j in 0..=second.lower.len() {
+            let sum = i + j;
+            if sum == 0 {
+                continue;
+            }
+
+            let premises = [premise(&first.lower, i), premise(&second.lower, j)];
+            let mut clause: Vec<i32> = premises.into_iter().flatten().collect();
+            if sum < modulus {
+                // Without a carry the lower digits are added.
+                clause.extend(carry);
+                clause.push(lower[sum - 1]);
+                sink.add_clause(&clause);
+            } else {
+                let carry = carry.unwrap();
+                let mut carry_clause = clause.clone();
+                carry_clause.push(carry);
+                sink.add_clause(&carry_clause);
+
+                if sum > modulus {
+                    clause.push(lower[sum - modulus - 1]);
+                    sink.add_clause(&clause);
+                }
+            }
+        }
+    }
+
+    for i in 0..=first.upper.len() {
+        for j in 0..=second.upper.len() {
+            let premises = [premise(&first.upper, i), premise(&second.upper, j)];
+            let clause: Vec<i32> = premises.into_iter().flatten().collect();
+
+            let add_upper = |sink: &mut _, mut clause: Vec<i32>, sum: usize| {
+                if sum == 0 {
+                    return;
+                }
+                // Larger values than max_upper are forbidden anyway, so they are saturated.
+                // Values which don't fit into this node can't occur.
+                if let Some(&output) = upper.get(sum.min(max_upper) - 1) {
+                    clause.push(output);
+                }
+                ClauseSink::add_clause(sink, &clause);
+            };
+
+            add_upper(sink, clause.clone(), i + j);
+            if let Some(carry) = carry {
+                let mut clause = clause;
+                clause.push(-carry);
+                add_upper(sink, clause, i + j + 1);
+            }
+        }
+    }
+
+    ModuloNode { lower, upper }
+}
+
+fn cardinality_network(sink: &mut impl ClauseSink, lits: &[i32], k: usize) {
+    let mut inputs: Vec<Option<i32>> = lits.iter().copied().map(Some).collect();
+    inputs.resize(lits.len().next_power_of_two(), None);
+
+    let outputs = sort(sink, inputs, k + 1);
+    if let Some(Some(exceeded)) = outputs.get(k) {
+        sink.add_clause(&[-exceeded]);
+    }
+}
+
+/// The largest `limit` outputs of an odd-even merge sorting network in descending order.
+/// `None` is a constant false wire. The number of inputs has to be a power of two.
+fn sort(
+    sink: &mut impl ClauseSink,
+    mut inputs: Vec<Option<i32>>,
+    limit: usize,
+) -> Vec<Option<i32>> {
+    if inputs.len() <= 1 {
+        return inputs;
+    }
+
+    let len = inputs.len() / 2;
+    let second = inputs.split_off(len);
+
+    // The largest outputs only depend on the largest outputs of both halves.
+    let mut first = sort(sink, inputs, limit);
+    let mut second = sort(sink, second, limit);
+    first.resize(len, None);
+    second.resize(len, None);
+
+    merge(sink, first, second, limit)
+}
+
+fn merge(
+    sink: &mut impl ClauseSink,
+    first: Vec<Option<i32>>,
+    second: Vec<Option<i32>>,
+    limit: usize,
+) -> Vec<Option<i32>> {
+    if first.len() == 1 {
+        return if limit >= 2 {
+            let (max, min) = comparator(sink, first[0], second[0]);
+            vec![max, min]
+        } else {
+            vec![or(sink, first[0], second[0])]
+        };
+    }
+
+    let split = |wires: &[Option<i32>], offset: usize| -> Vec<Option<i32>> {
+        wires.iter().skip(offset).step_by(2).copied().collect()
+    };
+    let evens = merge(sink, split(&first, 0), split(&second, 0), limit);
+    let odds = merge(sink, split(&first, 1), split(&second, 1), limit);
+
+    let mut outputs = vec![evens[0]];
+    for idx in 1..first.len() {
+        if outputs.len() >= limit {
+            return outputs;
+        }
+        let (odd, even) = (odds[idx - 1], evens[idx]);
+        if outputs.len() + 1 == limit {
+            // Only the larger output is needed.
+            outputs.push(or(sink, odd, even));
+        } else {
+            let (max, min) = comparator(sink, odd, even);
+            outputs.push(max);
+            outputs.push(min);
+        }
+    }
+    outputs.push(odds[first.len() - 1]);
+    outputs.truncate(limit);
+    outputs
+}
+
+/// Returns `(max, min)` of the inputs.
+/// Only the implications from the inputs to the outputs are encoded, which is sufficient to
+/// force the outputs to be true, if enough inputs are true.
+fn comparator(
+    sink: &mut impl ClauseSink,
+    first: Option<i32>,
+    second: Option<i32>,
+) -> (Option<i32>, Option<i32>) {
+    let (first, second) = match (first, second) {
+        (None, wire) | (wire, None) => return (wire, None),
+        (Some(first), Some(second)) => (first, second),
+    };
+
+    let max = sink.new_var();
+    let min = sink.new_var();
+    sink.add_clause(&[-first, max]);
+    sink.add_clause(&[-second, max]);
+    sink.add_clause(&[-first, -second, min]);
+
+    (Some(max), Some(min))
+}
+
+fn or(sink: &mut impl ClauseSink, first: Option<i32>, second: Option<i32>) -> Option<i32> {
+    let (first, second) = match (first, second) {
+        (None, wire) | (wire, None) => return wire,
+        (Some(first), Some(second)) => (first, second),
+    };
+
+    let output = sink.new_var();
+    sink.add_clause(&[-first, output]);
+    sink.add_clause(&[-second, output]);
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighted_totalizer_without_terms() {
+        let mut cnf = Cnf::default();
+        assert!(weighted_totalizer(&mut cnf, &[], 3).is_empty());
+        assert!(cnf.clauses.is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+//! Implementation of the [IPASIR](https://github.com/biotomas/ipasir) interface for incremental SAT solvers.
+//!
+//! The functions are exported with C linkage, so that the `cdylib` and `staticlib` builds of this crate can
+//! be used from any language through the `ipasir.h` header.
+//! All functions expect a valid pointer obtained from [`ipasir_init`] that hasn't been passed to
+//! [`ipasir_release`] yet.
+#![allow(clippy::missing_safety_doc)]
+
+use std::{
+    ffi::{c_char, c_int, c_void},
+    ptr::NonNull,
+};
+
+use crate::{Model, Result, Solver};
+
+/// Solver state of a IPASIR solver instance.
+struct IpasirSolver {
+    solver: Solver,
+
+    /// Clause which is currently added literal by literal.
+    clause: Vec<i32>,
+
+    /// Assumptions for the next call to `ipasir_solve`.
+    assumptions: Vec<i32>,
+
+    /// Model of the last call to `ipasir_solve`, if it was satisfiable.
+    model: Option<Model>,
+}
+
+/// Wrapper to move the user data pointer of callbacks into the solver.
+/// The IPASIR user is responsible for the thread safety of the data.
+struct UserData(*mut c_void);
+
+unsafe impl Send for UserData {}
+
+const SIGNATURE: &[u8] = concat!("dissat-", env!("CARGO_PKG_VERSION"), "\0").as_bytes();
+
+unsafe fn ipasir_solver<'a>(solver: *mut c_void) -> &'a mut IpasirSolver {
+    NonNull::new(solver.cast::<IpasirSolver>())
+        .expect("IPASIR solver pointer is null")
+        .as_mut()
+}
+
+#[no_mangle]
+pub extern "C" fn ipasir_signature() -> *const c_char {
+    SIGNATURE.as_ptr().cast()
+}
+
+#[no_mangle]
+pub extern "C" fn ipasir_init() -> *mut c_void {
+    let solver = Box::new(IpasirSolver {
+        solver: Solver::new(),
+        clause: Vec::new(),
+        assumptions: Vec::new(),
+        model: None,
+    });
+    Box::into_raw(solver).cast()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_release(solver: *mut c_void) {
+    drop(Box::from_raw(solver.cast::<IpasirSolver>()));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_add(solver: *mut c_void, lit_or_zero: i32) {
+    let solver = ipasir_solver(solver);
+    solver.model = None;
+
+    if lit_or_zero == 0 {
+        solver.solver.add_clause(solver.clause.drain(..));
+    } else {
+        solver.clause.push(lit_or_zero);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_assume(solver: *mut c_void, lit: i32) {
+    ipasir_solver(solver).assumptions.push(lit);
+}
+
+/// Returns 10 if the formula is satisfiable, 20 if it is unsatisfiable and 0 if the search was interrupted.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_solve(solver: *mut c_void) -> c_int {
+    let solver = ipasir_solver(solver);
+
+    let result = solver.solver.solve_with_assumptions(&solver.assumptions);
+    solver.assumptions.clear();
+
+    match result {
+        Result::Sat(model) => {
+            solver.model = Some(model);
+            10
+        }
+        Result::Unsat(_) => {
+            solver.model = None;
+            20
+        }
+        Result::Unknown => {
+            solver.model = None;
+            0
+        }
+    }
+}
+
+/// Returns `lit` if it is satisfied by the last model, `-lit` if it is falsified and 0 if the variable is unknown.
+/// Without a model of a satisfiable `ipasir_solve` call, every variable is unknown.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_val(solver: *mut c_void, lit: i32) -> i32 {
+    let Some(model) = ipasir_solver(solver).model.as_ref() else {
+        return 0;
+    };
+
+    match model.value(lit.abs()) {
+        Some(_) if model.lit(lit) => lit,
+        Some(_) => -lit,
+        None => 0,
+    }
+}
+
+/// Returns 1 if the assumption `lit` was used to prove unsatisfiability in the last `ipasir_solve` call.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_failed(solver: *mut c_void, lit: i32) -> c_int {
+    ipasir_solver(solver).solver.failed(lit) as c_int
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_set_terminate(
+    solver: *mut c_void,
+    data: *mut c_void,
+    terminate: Option<unsafe extern "C" fn(data: *mut c_void) -> c_int>,
+) {
+    let solver = &mut ipasir_solver(solver).solver;
+
+    match terminate {
+        Some(terminate) => {
+            let data = UserData(data);
+            solver.set_terminate(move || {
+                let data = &data;
+                terminate(data.0) != 0
+            });
+        }
+        None => solver.clear_terminate(),
+    }
+}
+
+/// Set a callback, which receives learned clauses of at most `max_length` literals as a zero terminated array.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_set_learn(
+    solver: *mut c_void,
+    data: *mut c_void,
+    max_length: c_int,
+    learn: Option<unsafe extern "C" fn(data: *mut c_void, clause: *mut i32)>,
+) {
+    let solver = &mut ipasir_solver(solver).solver;
+
+    match learn {
+        Some(learn) if max_length >= 0 => {
+            let data = UserData(data);
+            let mut buffer = Vec::new();
+            solver.set_learn(max_length as usize, u32::MAX, move |clause, _glue| {
+                let data = &data;
+                buffer.clear();
+                buffer.extend_from_slice(clause);
+                buffer.push(0);
+                learn(data.0, buffer.as_mut_ptr());
+            });
+        }
+        _ => solver.clear_learn(),
+    }
+}
@@ -3,8 +3,25 @@
 #![feature(iter_intersperse)]
 #![feature(array_windows)]
 
+pub mod aiger;
+mod biguint;
+pub mod bitvec;
+pub mod bmc;
+pub mod circuit;
 mod dimacs;
+pub mod encodings;
+pub mod ipasir;
+pub mod maxsat;
+pub mod mus;
+mod opb;
+pub mod portfolio;
 mod solver;
 mod util;
 
-pub use solver::{Model, Proof, Result, Solver};
+pub use biguint::BigUint;
+pub use dimacs::{Constraint, Dimacs, Icnf};
+pub use opb::{Opb, OpbInstance, PbConstraint, PbEncoding, Relation};
+pub use solver::{
+    BackboneResult, Blocking, Budget, ClauseImporter, Config, DecisionHeuristic, ModeStats, Model,
+    Models, Phase, Proof, Restarts, Result, SearchMode, Solver, TerminateHandle, UserPropagator,
+};
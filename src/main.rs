@@ -0,0 +1,250 @@
+//! Command line interface for DIMACS CNF and WCNF files.
+//!
+//! The output follows the SAT Competition and the MaxSAT Evaluation respectively. With `--count`
+//! the models of a CNF file are counted, with the output of the Model Counting Competition.
+//! `--cube` prints the formula with lookahead cubes as iCNF, `--conquer` solves the cubes directly.
+//! iCNF files are replayed against a single solver, with a result for every `a ... 0` line.
+//! `--threads` solves CNF files with a portfolio of differently configured solvers.
+
+use std::{error::Error, process::ExitCode};
+
+use dissat::{
+    maxsat::{self, MaxSatAlgorithm, Wcnf},
+    portfolio::Portfolio,
+    Dimacs, Icnf, Solver,
+};
+
+const USAGE: &str = "usage: dissat [options] <file.cnf | file.icnf | file.wcnf>
+
+options:
+    --linear         solve MaxSAT instances with linear SAT-UNSAT search instead of OLL
+    --count          count the models of a CNF file, projected on the `c p show` variables if present
+    --cube <depth>   print the CNF file with lookahead cubes up to `depth` decisions as iCNF
+    --conquer <depth>
+                     solve the CNF file by solving its lookahead cubes one after another
+    --threads <n>    solve the CNF file with a portfolio of n solvers sharing learned clauses";
+
+/// What to do with a CNF file.
+enum Mode {
+    Solve,
+    Count,
+    Cube(usize),
+    Conquer(usize),
+    Portfolio(usize),
+}
+
+struct Options {
+    path: String,
+    algorithm: MaxSatAlgorithm,
+    mode: Mode,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut path = None;
+    let mut algorithm = MaxSatAlgorithm::default();
+    let mut mode = Mode::Solve;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut depth = || {
+            args.next()
+                .and_then(|depth| depth.parse().ok())
+                .ok_or(format!("`{arg}` expects a depth\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "--linear" => algorithm = MaxSatAlgorithm::Linear,
+            "--count" => mode = Mode::Count,
+            "--cube" => mode = Mode::Cube(depth()?),
+            "--conquer" => mode = Mode::Conquer(depth()?),
+            "--threads" => {
+                let threads = args
+                    .next()
+                    .and_then(|threads| threads.parse().ok())
+                    .filter(|&threads| threads > 0)
+                    .ok_or(format!(
+                        "`{arg}` expects a positive number of threads\n{USAGE}"
+                    ))?;
+                mode = Mode::Portfolio(threads);
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`\n{USAGE}")),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("unexpected argument `{arg}`\n{USAGE}")),
+        }
+    }
+
+    Ok(Options {
+        path: path.ok_or(USAGE)?,
+        algorithm,
+        mode,
+    })
+}
+
+fn print_result(result: dissat::Result) -> ExitCode {
+    match result {
+        dissat::Result::Sat(model) => {
+            println!("s SATISFIABLE");
+            println!("{model}");
+            ExitCode::from(10)
+        }
+        dissat::Result::Unsat(_) => {
+            println!("s UNSATISFIABLE");
+            ExitCode::from(20)
+        }
+        dissat::Result::Unknown => {
+            println!("s UNKNOWN");
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn solve_cnf(input: &str) -> Result<ExitCode, Box<dyn Error>> {
+    let mut solver = Solver::from_dimacs(input)?;
+    Ok(print_result(solver.solve()))
+}
+
+/// Parse a CNF file without xor constraints.
+fn clauses_only(input: &str, mode: &str) -> Result<Solver, Box<dyn Error>> {
+    if input.lines().any(|line| line.trim_start().starts_with('x')) {
+        return Err(format!("xor constraints are not supported when {mode}").into());
+    }
+    Solver::from_dimacs(input)
+}
+
+fn cube_cnf(input: &str, depth: usize) -> Result<ExitCode, Box<dyn Error>> {
+    let mut solver = clauses_only(input, "cubing")?;
+    let cubes = solver.cubes(depth).ok_or("cubing was interrupted")?;
+
+    println!("p inccnf");
+    for clause in Dimacs::parse(input)? {
+        let lits: Vec<String> = clause.iter().map(|lit| lit.to_string()).collect();
+        println!("{} 0", lits.join(" "));
+    }
+    for cube in cubes {
+        let lits: Vec<String> = cube.iter().map(|lit| lit.to_string()).collect();
+        println!("a {} 0", lits.join(" "));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn conquer_cnf(input: &str, depth: usize) -> Result<ExitCode, Box<dyn Error>> {
+    let mut solver = clauses_only(input, "cubing")?;
+    let result = match solver.cubes(depth) {
+        Some(cubes) => {
+            println!("c {} cubes", cubes.len());
+            solver.conquer(&cubes)
+        }
+        None => dissat::Result::Unknown,
+    };
+    Ok(print_result(result))
+}
+
+fn solve_portfolio(input: &str, threads: usize) -> Result<ExitCode, Box<dyn Error>> {
+    clauses_only(input, "solving with threads")?;
+    let clauses = Dimacs::parse(input)?;
+    Ok(print_result(Portfolio::new(threads).solve(&clauses)))
+}
+
+/// Variables of the `c p show ... 0` lines, which select the projection for counting.
+fn projection(input: &str) -> Result<Option<Vec<i32>>, Box<dyn Error>> {
+    let mut projection = None;
+    for line in input.lines().map(str::trim) {
+        let Some(vars) = line.strip_prefix("c p show") else {
+            continue;
+        };
+        let projection = projection.get_or_insert_with(Vec::new);
+        for token in vars.split_whitespace() {
+            match token.parse::<i32>()? {
+                0 => break,
+                var if var > 0 => projection.push(var),
+                var => return Err(format!("invalid projection variable {var}").into()),
+            }
+        }
+    }
+    Ok(projection)
+}
+
+fn count_cnf(input: &str) -> Result<ExitCode, Box<dyn Error>> {
+    let mut solver = clauses_only(input, "counting")?;
+    let (count, kind) = match projection(input)? {
+        Some(projection) => (solver.count_projected_models(&projection), "pmc"),
+        None => (solver.count_models(), "mc"),
+    };
+    let Some(count) = count else {
+        println!("s UNKNOWN");
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    if count.is_zero() {
+        println!("s UNSATISFIABLE");
+    } else {
+        println!("s SATISFIABLE");
+    }
+    println!("c s type {kind}");
+    println!("c s exact arb int {count}");
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Replay the clauses and solve calls of an iCNF file.
+fn solve_icnf(input: &str) -> Result<ExitCode, Box<dyn Error>> {
+    let mut solver = Solver::new();
+    for line in Dimacs::parse_icnf(input)? {
+        match line {
+            Icnf::Clause(clause) => solver.add_clause(clause),
+            Icnf::Assumptions(assumptions) => {
+                print_result(solver.solve_with_assumptions(&assumptions));
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn solve_wcnf(input: &str, algorithm: MaxSatAlgorithm) -> Result<ExitCode, Box<dyn Error>> {
+    let wcnf = Wcnf::parse(input)?;
+    let result = maxsat::solve(&wcnf, algorithm, |cost| println!("o {cost}"));
+    print!("{}", result.evaluation_output(wcnf.num_vars));
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run(options: Options) -> Result<ExitCode, Box<dyn Error>> {
+    let input = std::fs::read_to_string(&options.path)
+        .map_err(|err| format!("can't read {}: {err}", options.path))?;
+
+    if options.path.ends_with(".wcnf") {
+        return solve_wcnf(&input, options.algorithm);
+    }
+    let icnf_header = input
+        .lines()
+        .any(|line| line.trim().starts_with("p inccnf"));
+    if options.path.ends_with(".icnf") || icnf_header {
+        if !matches!(options.mode, Mode::Solve) {
+            return Err(
+                "`--count`, `--cube`, `--conquer` and `--threads` are not supported for iCNF files"
+                    .into(),
+            );
+        }
+        return solve_icnf(&input);
+    }
+    match options.mode {
+        Mode::Solve => solve_cnf(&input),
+        Mode::Count => count_cnf(&input),
+        Mode::Cube(depth) => cube_cnf(&input, depth),
+        Mode::Conquer(depth) => conquer_cnf(&input, depth),
+        Mode::Portfolio(threads) => solve_portfolio(&input, threads),
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    run(options).unwrap_or_else(|err| {
+        eprintln!("c error: {err}");
+        ExitCode::FAILURE
+    })
+}
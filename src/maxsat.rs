@@ -0,0 +1,319 @@
+//! Weighted partial MaxSAT on top of the incremental solver.
+//!
+//! [`solve`] minimizes the total weight of the falsified soft clauses, either with core-guided
+//! search (OLL with totalizers, as in RC2) or with a linear SAT-UNSAT search, which tightens a
+//! pseudo-Boolean upper bound after every model.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+};
+
+use crate::{encodings, Model, PbConstraint, PbEncoding, Relation, Solver};
+
+/// Weighted partial MaxSAT instance.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Wcnf {
+    /// Largest variable of the instance.
+    pub num_vars: usize,
+    pub hard: Vec<Vec<i32>>,
+    /// Soft clauses with their weights.
+    pub soft: Vec<(u64, Vec<i32>)>,
+}
+
+impl Wcnf {
+    /// Parse the WCNF format of the MaxSAT Evaluation. Both the format since 2022 with `h` for hard
+    /// clauses and the older format with a `p wcnf` header and a top weight are supported.
+    pub fn parse(input: &str) -> Result<Wcnf, Box<dyn Error>> {
+        let mut wcnf = Wcnf::default();
+        let mut top = None;
+        let mut total_weight: u64 = 0;
+
+        for line in input.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('c') {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            if line.starts_with('p') {
+                let header: Vec<&str> = tokens.collect();
+                if !(4..=5).contains(&header.len()) || header[1] != "wcnf" {
+                    return Err(format!("invalid header `{line}`").into());
+                }
+                wcnf.num_vars = header[2].parse()?;
+                top = header.get(4).map(|top| top.parse::<u64>()).transpose()?;
+                continue;
+            }
+
+            let weight = match tokens.next() {
+                Some("h") => None,
+                Some(weight) => Some(weight.parse::<u64>()?),
+                None => unreachable!(),
+            };
+
+            let mut clause = Vec::new();
+            for token in tokens.by_ref() {
+                match token.parse::<i32>()? {
+                    0 => break,
+                    lit => {
+                        wcnf.num_vars = wcnf.num_vars.max(lit.unsigned_abs() as usize);
+                        clause.push(lit);
+                    }
+                }
+            }
+            if clause.last().is_some() && line.split_whitespace().last() != Some("0") {
+                return Err(format!("clause `{line}` isn't terminated by 0").into());
+            }
+
+            match weight {
+                Some(weight) if top.is_none_or(|top| weight < top) => {
+                    total_weight = total_weight
+                        .checked_add(weight)
+                        .filter(|&total| total <= i64::MAX as u64)
+                        .ok_or("sum of the soft clause weights is too large")?;
+                    if weight > 0 {
+                        wcnf.soft.push((weight, clause));
+                    }
+                }
+                _ => wcnf.hard.push(clause),
+            }
+        }
+
+        Ok(wcnf)
+    }
+
+    /// Total weight of the soft clauses falsified by `model`.
+    pub fn cost(&self, model: &Model) -> u64 {
+        self.soft
+            .iter()
+            .filter(|(_, clause)| !clause.iter().any(|&lit| model.lit(lit)))
+            .map(|(weight, _)| weight)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaxSatAlgorithm {
+    /// Core-guided search, which relaxes cores with totalizers.
+    #[default]
+    Oll,
+    /// Models of decreasing cost until the bound is unsatisfiable.
+    Linear,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaxSatResult {
+    Optimum {
+        cost: u64,
+        model: Model,
+    },
+    /// The search was interrupted after a model was found, or the bound on the cost of the
+    /// model couldn't be encoded.
+    Satisfiable {
+        cost: u64,
+        model: Model,
+    },
+    /// The hard clauses are unsatisfiable.
+    Unsat,
+    Unknown,
+}
+
+impl MaxSatResult {
+    /// Status and model lines in the format of the MaxSAT Evaluation. The values of the first
+    /// `num_vars` variables are printed as a string of `0` and `1`.
+    pub fn evaluation_output(&self, num_vars: usize) -> String {
+        let model_line = |model: &Model| -> String {
+            let values: String = (1..=num_vars as i32)
+                .map(|var| if model.lit(var) { '1' } else { '0' })
+                .collect();
+            format!("v {values}\n")
+        };
+
+        match self {
+            MaxSatResult::Optimum { model, .. } => {
+                format!("s OPTIMUM FOUND\n{}", model_line(model))
+            }
+            MaxSatResult::Satisfiable { model, .. } => {
+                format!("s SATISFIABLE\n{}", model_line(model))
+            }
+            MaxSatResult::Unsat => "s UNSATISFIABLE\n".to_string(),
+            MaxSatResult::Unknown => "s UNKNOWN\n".to_string(),
+        }
+    }
+}
+
+/// Minimize the weight of the falsified soft clauses of `wcnf`.
+/// `progress` is called with the cost of every model that improves the best known cost.
+pub fn solve(
+    wcnf: &Wcnf,
+    algorithm: MaxSatAlgorithm,
+    mut progress: impl FnMut(u64),
+) -> MaxSatResult {
+    let mut solver = Solver::new();
+    while solver.num_vars() < wcnf.num_vars {
+        solver.new_var();
+    }
+    for clause in &wcnf.hard {
+        solver.add_clause(clause.iter().copied());
+    }
+
+    // Literals, which are true if their soft clauses are satisfied, with their merged weights.
+    let mut softs = BTreeMap::new();
+    for (weight, clause) in &wcnf.soft {
+        let lit = match clause[..] {
+            // Empty soft clauses are always falsified and don't influence the optimization.
+            [] => continue,
+            [lit] => lit,
+            _ => {
+                let lit = solver.new_var();
+                solver.add_clause(clause.iter().copied().chain([-lit]));
+                lit
+            }
+        };
+        *softs.entry(lit).or_insert(0) += weight;
+    }
+
+    match algorithm {
+        MaxSatAlgorithm::Oll => oll(wcnf, solver, softs, &mut progress),
+        MaxSatAlgorithm::Linear => linear(wcnf, solver, softs, &mut progress),
+    }
+}
+
+fn oll(
+    wcnf: &Wcnf,
+    mut solver: Solver,
+    mut weights: BTreeMap<i32, u64>,
+    progress: &mut impl FnMut(u64),
+) -> MaxSatResult {
+    // Outputs of the totalizers over the relaxed cores. Assuming the negation of an output bounds
+    // the number of falsified literals of the core.
+    let mut totalizers: Vec<Vec<i32>> = Vec::new();
+    let mut bounds: HashMap<i32, (usize, usize)> = HashMap::new();
+
+    loop {
+        let assumptions: Vec<i32> = weights.keys().copied().collect();
+        match solver.solve_with_assumptions(&assumptions) {
+            crate::Result::Sat(model) => {
+                let cost = wcnf.cost(&model);
+                progress(cost);
+                return MaxSatResult::Optimum { cost, model };
+            }
+            crate::Result::Unknown => return MaxSatResult::Unknown,
+            crate::Result::Unsat(_) => {}
+        }
+
+        let core = solver.failed_assumptions().to_vec();
+        if core.is_empty() {
+            return MaxSatResult::Unsat;
+        }
+
+        let min_weight = core.iter().map(|lit| weights[lit]).min().unwrap();
+        for &lit in &core {
+            let weight = weights.get_mut(&lit).unwrap();
+            *weight -= min_weight;
+            if *weight == 0 {
+                weights.remove(&lit);
+            }
+
+            // Relax the bound of a totalizer by one.
+            if let Some(&(totalizer, bound)) = bounds.get(&lit) {
+                if let Some(&output) = totalizers[totalizer].get(bound + 1) {
+                    *weights.entry(-output).or_insert(0) += min_weight;
+                    bounds.insert(-output, (totalizer, bound + 1));
+                }
+            }
+        }
+
+        if let [lit] = core[..] {
+            solver.add_clause([-lit]);
+            continue;
+        }
+
+        // At least one literal of the core is falsified, it costs extra to falsify more of them.
+        let falsified: Vec<i32> = core.iter().map(|&lit| -lit).collect();
+        let outputs = encodings::totalizer_outputs(&mut solver, &falsified);
+        let output = outputs[1];
+        *weights.entry(-output).or_insert(0) += min_weight;
+        bounds.insert(-output, (totalizers.len(), 1));
+        totalizers.push(outputs);
+    }
+}
+
+fn linear(
+    wcnf: &Wcnf,
+    mut solver: Solver,
+    weights: BTreeMap<i32, u64>,
+    progress: &mut impl FnMut(u64),
+) -> MaxSatResult {
+    // The constant cost of empty soft clauses, which isn't part of the bound.
+    let base_cost: u64 = wcnf
+        .soft
+        .iter()
+        .filter(|(_, clause)| clause.is_empty())
+        .map(|(weight, _)| weight)
+        .sum();
+    let terms: Vec<(i64, i32)> = weights
+        .iter()
+        .map(|(&lit, &weight)| (weight as i64, -lit))
+        .collect();
+
+    let mut best: Option<(u64, Model)> = None;
+    loop {
+        match solver.solve() {
+            crate::Result::Sat(model) => {
+                let cost = wcnf.cost(&model);
+                progress(cost);
+                best = Some((cost, model));
+                if cost == base_cost {
+                    break;
+                }
+
+                let bound = PbConstraint {
+                    terms: terms.clone(),
+                    relation: Relation::LessEq,
+                    rhs: (cost - base_cost - 1) as i64,
+                };
+                if solver
+                    .add_pb_constraint(&bound, PbEncoding::default())
+                    .is_err()
+                {
+                    // The weights are too large to encode the bound, so optimality can't be proven.
+                    let (cost, model) = best.unwrap();
+                    return MaxSatResult::Satisfiable { cost, model };
+                }
+            }
+            crate::Result::Unsat(_) => break,
+            crate::Result::Unknown => {
+                return match best {
+                    Some((cost, model)) => MaxSatResult::Satisfiable { cost, model },
+                    None => MaxSatResult::Unknown,
+                }
+            }
+        }
+    }
+
+    match best {
+        Some((cost, model)) => MaxSatResult::Optimum { cost, model },
+        None => MaxSatResult::Unsat,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Wcnf;
+
+    #[test]
+    fn parse_formats() {
+        let old = "c old format\np wcnf 3 4 10\n10 1 2 0\n10 -1 0\n3 -2 0\n2 3 -2 0\n";
+        let new = "c new format\nh 1 2 0\nh -1 0\n3 -2 0\n2 3 -2 0\n";
+
+        let wcnf = Wcnf::parse(old).unwrap();
+        assert_eq!(wcnf.num_vars, 3);
+        assert_eq!(wcnf.hard, vec![vec![1, 2], vec![-1]]);
+        assert_eq!(wcnf.soft, vec![(3, vec![-2]), (2, vec![3, -2])]);
+        assert_eq!(Wcnf::parse(new).unwrap(), wcnf);
+
+        assert!(Wcnf::parse("h 1 2\n").is_err());
+        assert!(Wcnf::parse("p cnf 1 1\n1 0\n").is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! Minimal unsatisfiable subsets of clauses.
+//!
+//! Every clause gets a selector variable, which enables the clause when assumed. [`extract`] first
+//! restricts the clauses to an unsat core and then tries to delete the remaining clauses one by
+//! one. Whenever a clause turns out to be necessary, model rotation flips the witness model to
+//! find further necessary clauses without calling the solver.
+
+use std::collections::HashMap;
+
+use crate::{Model, Solver};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MusResult {
+    /// Indices of the clauses of a minimal unsatisfiable subset, in increasing order.
+    /// Removing any of them makes the remaining clauses satisfiable.
+    Mus(Vec<usize>),
+    /// The clauses are satisfiable, so there is no unsatisfiable subset. The model only contains
+    /// the variables of the clauses.
+    Satisfiable(Model),
+}
+
+/// Incremental solver over the clauses with selector variables.
+struct Extractor<'a> {
+    clauses: &'a [Vec<i32>],
+    /// Largest variable of the clauses, the selector variables follow.
+    num_vars: usize,
+    solver: Solver,
+    selectors: Vec<i32>,
+    /// Clauses, which are known to be part of the MUS.
+    necessary: Vec<bool>,
+    /// Indices of the clauses, in which a variable occurs.
+    occurrences: HashMap<i32, Vec<usize>>,
+}
+
+impl<'a> Extractor<'a> {
+    fn new(clauses: &'a [Vec<i32>]) -> Self {
+        let mut solver = Solver::new();
+        let num_vars = clauses
+            .iter()
+            .flatten()
+            .map(|lit| lit.unsigned_abs() as usize)
+            .max()
+            .unwrap_or(0);
+        while solver.num_vars() < num_vars {
+            solver.new_var();
+        }
+
+        let mut selectors = Vec::with_capacity(clauses.len());
+        let mut occurrences: HashMap<i32, Vec<usize>> = HashMap::new();
+        for (idx, clause) in clauses.iter().enumerate() {
+            let selector = solver.new_var();
+            solver.add_clause(clause.iter().copied().chain([-selector]));
+            selectors.push(selector);
+
+            for &lit in clause {
+                let clauses = occurrences.entry(lit.abs()).or_default();
+                if clauses.last() != Some(&idx) {
+                    clauses.push(idx);
+                }
+            }
+        }
+
+        Extractor {
+            clauses,
+            num_vars,
+            solver,
+            selectors,
+            necessary: vec![false; clauses.len()],
+            occurrences,
+        }
+    }
+
+    /// Solve with the clauses `enabled`. Returns the indices of the failed assumptions as core or
+    /// the model, if the clauses are satisfiable.
+    fn solve(&mut self, enabled: &[usize]) -> Result<Vec<usize>, Model> {
+        let assumptions: Vec<i32> = enabled.iter().map(|&idx| self.selectors[idx]).collect();
+        match self.solver.solve_with_assumptions(&assumptions) {
+            crate::Result::Sat(model) => Err(model),
+            crate::Result::Unsat(_) => Ok(enabled
+                .iter()
+                .copied()
+                .filter(|&idx| self.solver.failed(self.selectors[idx]))
+                .collect()),
+            crate::Result::Unknown => unreachable!("the solver has no budget"),
+        }
+    }
+
+    /// Mark `idx` as necessary and find further necessary clauses among `candidates` by flipping
+    /// the variables of the clauses falsified by `model`.
+    fn rotate(&mut self, idx: usize, model: &Model, candidates: &[bool]) {
+        self.necessary[idx] = true;
+
+        // Clauses, which are the only falsified candidate under a model, with that model.
+        let mut pending = vec![(idx, model.as_vec())];
+        while let Some((idx, assignment)) = pending.pop() {
+            for &lit in &self.clauses[idx] {
+                let var = lit.unsigned_abs() as usize - 1;
+                let mut flipped = assignment.clone();
+                flipped[var] = -flipped[var];
+
+                let satisfied = |clause: &[i32]| {
+                    clause
+                        .iter()
+                        .any(|&lit| flipped[lit.unsigned_abs() as usize - 1] == lit)
+                };
+                // Flipping a literal of the falsified clause satisfies it, so another clause
+                // containing the variable can become the only falsified one.
+                let falsified: Vec<usize> = self.occurrences[&lit.abs()]
+                    .iter()
+                    .copied()
+                    .filter(|&other| candidates[other] && !satisfied(&self.clauses[other]))
+                    .collect();
+                if let [other] = falsified[..] {
+                    if !self.necessary[other] {
+                        self.necessary[other] = true;
+                        pending.push((other, flipped));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Find a minimal unsatisfiable subset of `clauses`, e.g. as returned by
+/// [`Dimacs::parse`](crate::Dimacs::parse).
+pub fn extract(clauses: &[Vec<i32>]) -> MusResult {
+    let mut extractor = Extractor::new(clauses);
+
+    let all: Vec<usize> = (0..clauses.len()).collect();
+    let mut core = match extractor.solve(&all) {
+        Ok(core) => core,
+        Err(mut model) => {
+            model.truncate(extractor.num_vars);
+            return MusResult::Satisfiable(model);
+        }
+    };
+
+    let mut next = 0;
+    while let Some(&idx) = core.get(next) {
+        if extractor.necessary[idx] {
+            next += 1;
+            continue;
+        }
+
+        let rest: Vec<usize> = core.iter().copied().filter(|&other| other != idx).collect();
+        match extractor.solve(&rest) {
+            Ok(smaller) => {
+                // The clause isn't needed, the core might even shrink further.
+                extractor.solver.add_clause([-extractor.selectors[idx]]);
+                core = smaller;
+                next = 0;
+            }
+            Err(model) => {
+                let mut candidates = vec![false; clauses.len()];
+                for &other in &core {
+                    candidates[other] = true;
+                }
+                extractor.rotate(idx, &model, &candidates);
+                next += 1;
+            }
+        }
+    }
+
+    core.sort_unstable();
+    MusResult::Mus(core)
+}
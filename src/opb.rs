@@ -0,0 +1,589 @@
+use std::{collections::HashMap, error::Error};
+
+use crate::{
+    encodings::{self, AtMostKEncoding},
+    Solver,
+};
+
+/// Parser for the OPB format of the pseudo-Boolean competition.
+pub struct Opb;
+
+/// Relation between the weighted sum and the right hand side of a [`PbConstraint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    GreaterEq,
+    Eq,
+    LessEq,
+}
+
+/// Linear pseudo-Boolean constraint `Σ a_i l_i ⋈ rhs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PbConstraint {
+    /// Coefficients and DIMACS style literals.
+    pub terms: Vec<(i64, i32)>,
+    pub relation: Relation,
+    pub rhs: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpbInstance {
+    /// Largest variable of the instance.
+    pub num_vars: usize,
+    /// Terms of the objective function, which should be minimized.
+    pub objective: Option<Vec<(i64, i32)>>,
+    pub constraints: Vec<PbConstraint>,
+}
+
+/// CNF encoding used for pseudo-Boolean constraints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PbEncoding {
+    /// Binary decision diagram over the terms.
+    Bdd,
+    /// Odd-even merge sorting network over the unary representation of the terms. Constraints
+    /// whose unary representation exceeds 2^16 literals fall back to [`Adder`].
+    ///
+    /// [`Adder`]: PbEncoding::Adder
+    SortingNetwork,
+    /// Generalized totalizer.
+    #[default]
+    Totalizer,
+    /// Network of binary adders, whose sum is compared with the right hand side.
+    Adder,
+}
+
+impl Opb {
+    pub fn parse(input: &str) -> Result<OpbInstance, Box<dyn Error>> {
+        let input: String = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('*'))
+            .flat_map(|line| [line, "\n"])
+            .collect();
+
+        let mut instance = OpbInstance::default();
+
+        for statement in input.split(';').map(str::trim) {
+            if statement.is_empty() {
+                continue;
+            }
+
+            if let Some(objective) = statement.strip_prefix("min:") {
+                let mut tokens = objective.split_whitespace().peekable();
+                let terms = Self::parse_terms(&mut tokens, &mut instance.num_vars)?;
+                if let Some(token) = tokens.next() {
+                    return Err(format!("unexpected token `{token}` in objective").into());
+                }
+                instance.objective = Some(terms);
+                continue;
+            }
+
+            let mut tokens = statement.split_whitespace().peekable();
+            let terms = Self::parse_terms(&mut tokens, &mut instance.num_vars)?;
+            let relation = match tokens.next() {
+                Some(">=") => Relation::GreaterEq,
+                Some("=") => Relation::Eq,
+                Some("<=") => Relation::LessEq,
+                token => return Err(format!("expected relation, found {token:?}").into()),
+            };
+            let rhs = tokens.next().ok_or("missing right hand side")?.parse()?;
+            if let Some(token) = tokens.next() {
+                return Err(format!("unexpected token `{token}` after right hand side").into());
+            }
+
+            instance.constraints.push(PbConstraint {
+                terms,
+                relation,
+                rhs,
+            });
+        }
+
+        Ok(instance)
+    }
+
+    /// Parse `coefficient literal` pairs until a relation is found.
+    fn parse_terms<'a>(
+        tokens: &mut std::iter::Peekable<impl Iterator<Item = &'a str>>,
+        num_vars: &mut usize,
+    ) -> Result<Vec<(i64, i32)>, Box<dyn Error>> {
+        let mut terms = Vec::new();
+
+        while let Some(&token) = tokens.peek() {
+            if matches!(token, ">=" | "=" | "<=") {
+                break;
+            }
+            tokens.next();
+
+            let coefficient: i64 = token.parse()?;
+            let lit = tokens.next().ok_or("missing literal after coefficient")?;
+            let (negated, var) = match lit.strip_prefix('~') {
+                Some(var) => (true, var),
+                None => (false, lit),
+            };
+            let var: i32 = var
+                .strip_prefix('x')
+                .ok_or_else(|| format!("invalid literal `{lit}`"))?
+                .parse()?;
+            if var <= 0 {
+                return Err(format!("invalid variable `{lit}`").into());
+            }
+
+            *num_vars = (*num_vars).max(var as usize);
+            terms.push((coefficient, if negated { -var } else { var }));
+        }
+
+        Ok(terms)
+    }
+}
+
+/// Largest sum of coefficients, which is expanded into a sorting network.
+const MAX_UNARY_SIZE: u64 = 1 << 16;
+
+/// Constraint `Σ a_i l_i >= rhs` with positive coefficients.
+type NormalizedConstraint = (Vec<(u64, i32)>, i64);
+
+/// Normalize `Σ a_i l_i ⋈ rhs` into constraints `Σ a_i l_i >= rhs` with positive coefficients.
+/// Fails if the right hand side of a normalized constraint doesn't fit into an `i64`.
+fn normalize(constraint: &PbConstraint) -> Result<Vec<NormalizedConstraint>, Box<dyn Error>> {
+    const OVERFLOW: &str = "pseudo-Boolean constraint overflows 64-bit integers";
+
+    let greater_eq = |terms: &[(i64, i32)], rhs: Option<i64>| -> Result<_, Box<dyn Error>> {
+        let mut rhs = rhs.ok_or(OVERFLOW)?;
+        let mut normalized = Vec::new();
+        for &(coefficient, lit) in terms {
+            if coefficient > 0 {
+                normalized.push((coefficient as u64, lit));
+            } else if coefficient < 0 {
+                // a * l = a - a * ~l
+                rhs = rhs.checked_sub(coefficient).ok_or(OVERFLOW)?;
+                normalized.push((coefficient.unsigned_abs(), -lit));
+            }
+        }
+        Ok((normalized, rhs))
+    };
+    let less_eq = || {
+        let negated: Vec<(i64, i32)> = constraint
+            .terms
+            .iter()
+            .map(|&(coefficient, lit)| Some((coefficient.checked_neg()?, lit)))
+            .collect::<Option<_>>()
+            .ok_or(OVERFLOW)?;
+        greater_eq(&negated, constraint.rhs.checked_neg())
+    };
+
+    Ok(match constraint.relation {
+        Relation::GreaterEq => vec![greater_eq(&constraint.terms, Some(constraint.rhs))?],
+        Relation::LessEq => vec![less_eq()?],
+        Relation::Eq => vec![
+            greater_eq(&constraint.terms, Some(constraint.rhs))?,
+            less_eq()?,
+        ],
+    })
+}
+
+/// Node of an encoding, which might be a constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Node {
+    True,
+    False,
+    Lit(i32),
+}
+
+/// Adds the clauses of an encoding to the solver.
+struct Encoder<'a> {
+    solver: &'a mut Solver,
+}
+
+impl Encoder<'_> {
+    fn new_var(&mut self) -> i32 {
+        self.solver.new_var()
+    }
+
+    fn clause<const N: usize>(&mut self, lits: [i32; N]) {
+        self.solver.add_clause(lits);
+    }
+
+    fn assert(&mut self, node: Node) {
+        match node {
+            Node::True => {}
+            Node::False => self.clause([]),
+            Node::Lit(lit) => self.clause([lit]),
+        }
+    }
+
+    /// `Σ a_i l_i >= rhs` with positive coefficients.
+    fn encode(&mut self, terms: &[(u64, i32)], rhs: u64, encoding: PbEncoding) {
+        match encoding {
+            PbEncoding::Bdd => self.bdd(terms, rhs),
+            PbEncoding::SortingNetwork
+                if terms
+                    .iter()
+                    .map(|(coefficient, _)| coefficient)
+                    .sum::<u64>()
+                    > MAX_UNARY_SIZE =>
+            {
+                self.adder(terms, rhs)
+            }
+            PbEncoding::SortingNetwork => self.sorting_network(terms, rhs),
+            PbEncoding::Totalizer => self.totalizer(terms, rhs),
+            PbEncoding::Adder => self.adder(terms, rhs),
+        }
+    }
+
+    fn bdd(&mut self, terms: &[(u64, i32)], rhs: u64) {
+        let mut suffix_sums = vec![0; terms.len() + 1];
+        for idx in (0..terms.len()).rev() {
+            suffix_sums[idx] = suffix_sums[idx + 1] + terms[idx].0;
+        }
+
+        let root = self.bdd_node(terms, &suffix_sums, 0, rhs, &mut HashMap::new());
+        self.assert(root);
+    }
+
+    /// Node which implies `Σ_{j >= idx} a_j l_j >= rhs`.
+    fn bdd_node(
+        &mut self,
+        terms: &[(u64, i32)],
+        suffix_sums: &[u64],
+        idx: usize,
+        rhs: u64,
+        memo: &mut HashMap<(usize, u64), Node>,
+    ) -> Node {
+        if rhs == 0 {
+            return Node::True;
+        }
+        if suffix_sums[idx] < rhs {
+            return Node::False;
+        }
+        if let Some(&node) = memo.get(&(idx, rhs)) {
+            return node;
+        }
+
+        let (coefficient, lit) = terms[idx];
+        let high = self.bdd_node(
+            terms,
+            suffix_sums,
+            idx + 1,
+            rhs.saturating_sub(coefficient),
+            memo,
+        );
+        let low = self.bdd_node(terms, suffix_sums, idx + 1, rhs, memo);
+
+        let node = match (high, low) {
+            (_, Node::True) => Node::True,
+            (Node::False, _) => Node::False,
+            _ => {
+                let var = self.new_var();
+                // var -> high and var -> (lit or low)
+                if let Node::Lit(high) = high {
+                    self.clause([-var, high]);
+                }
+                match low {
+                    Node::Lit(low) => self.clause([-var, lit, low]),
+                    _ => self.clause([-var, lit]),
+                }
+                Node::Lit(var)
+            }
+        };
+
+        memo.insert((idx, rhs), node);
+        node
+    }
+
+    fn sorting_network(&mut self, terms: &[(u64, i32)], rhs: u64) {
+        // Repeated literals are separate wires of the network.
+        let inputs: Vec<i32> = terms
+            .iter()
+            .flat_map(|&(coefficient, lit)| std::iter::repeat_n(lit, coefficient as usize))
+            .collect();
+
+        encodings::at_least_k(
+            self.solver,
+            &inputs,
+            rhs as usize,
+            AtMostKEncoding::CardinalityNetwork,
+        );
+    }
+
+    fn totalizer(&mut self, terms: &[(u64, i32)], rhs: u64) {
+        // Σ a_i l_i >= rhs iff Σ a_i ~l_i <= Σ a_i - rhs
+        let sum: u64 = terms.iter().map(|(coefficient, _)| coefficient).sum();
+        if sum < rhs {
+            self.clause([]);
+            return;
+        }
+        let limit = sum - rhs;
+
+        let negated: Vec<(u64, i32)> = terms
+            .iter()
+            .map(|&(coefficient, lit)| (coefficient, -lit))
+            .collect();
+        let outputs = encodings::weighted_totalizer(self.solver, &negated, limit + 1);
+
+        if let Some(&exceeded) = outputs.get(&(limit + 1)) {
+            self.clause([-exceeded]);
+        }
+    }
+
+    fn adder(&mut self, terms: &[(u64, i32)], rhs: u64) {
+        let mut buckets: Vec<Vec<i32>> = Vec::new();
+        for &(coefficient, lit) in terms {
+            for bit in 0..u64::BITS as usize {
+                if coefficient >> bit & 1 == 1 {
+                    if buckets.len() <= bit {
+                        buckets.resize(bit + 1, Vec::new());
+                    }
+                    buckets[bit].push(lit);
+                }
+            }
+        }
+
+        // Binary representation of the sum, least significant bit first.
+        let mut sum_bits = Vec::new();
+        let mut bit = 0;
+        while bit < buckets.len() {
+            while buckets[bit].len() >= 2 {
+                let x = buckets[bit].pop().unwrap();
+                let y = buckets[bit].pop().unwrap();
+                let (sum, carry) = match buckets[bit].pop() {
+                    Some(z) => self.full_adder(x, y, z),
+                    None => self.half_adder(x, y),
+                };
+                buckets[bit].push(sum);
+                if buckets.len() <= bit + 1 {
+                    buckets.push(Vec::new());
+                }
+                buckets[bit + 1].push(carry);
+            }
+
+            sum_bits.push(buckets[bit].pop());
+            bit += 1;
+        }
+
+        // Compare the sum with rhs starting at the least significant bit.
+        let num_bits = sum_bits
+            .len()
+            .max((u64::BITS - rhs.leading_zeros()) as usize);
+        let mut greater_eq = Node::True;
+        for bit in 0..num_bits {
+            let sum_bit = sum_bits.get(bit).copied().flatten();
+            greater_eq = if rhs >> bit & 1 == 1 {
+                self.and(sum_bit, greater_eq)
+            } else {
+                self.or(sum_bit, greater_eq)
+            };
+        }
+        self.assert(greater_eq);
+    }
+
+    /// Node which implies `lit and node`.
+    fn and(&mut self, lit: Option<i32>, node: Node) -> Node {
+        match (lit, node) {
+            (None, _) | (_, Node::False) => Node::False,
+            (Some(lit), Node::True) => Node::Lit(lit),
+            (Some(lit), Node::Lit(other)) => {
+                let var = self.new_var();
+                self.clause([-var, lit]);
+                self.clause([-var, other]);
+                Node::Lit(var)
+            }
+        }
+    }
+
+    /// Node which implies `lit or node`.
+    fn or(&mut self, lit: Option<i32>, node: Node) -> Node {
+        match (lit, node) {
+            (_, Node::True) => Node::True,
+            (None, node) => node,
+            (Some(lit), Node::False) => Node::Lit(lit),
+            (Some(lit), Node::Lit(other)) => {
+                let var = self.new_var();
+                self.clause([-var, lit, other]);
+                Node::Lit(var)
+            }
+        }
+    }
+
+    /// Returns `(sum, carry)` of the inputs.
+    fn full_adder(&mut self, x: i32, y: i32, z: i32) -> (i32, i32) {
+        let sum = self.new_var();
+        let carry = self.new_var();
+
+        for pattern in 0..8 {
+            let (px, py, pz) = (pattern & 1 == 1, pattern & 2 == 2, pattern & 4 == 4);
+            let sign = |lit: i32, value: bool| if value { -lit } else { lit };
+            let parity = px ^ py ^ pz;
+            self.clause([sign(x, px), sign(y, py), sign(z, pz), sign(sum, !parity)]);
+        }
+
+        self.clause([-x, -y, carry]);
+        self.clause([-x, -z, carry]);
+        self.clause([-y, -z, carry]);
+        self.clause([x, y, -carry]);
+        self.clause([x, z, -carry]);
+        self.clause([y, z, -carry]);
+
+        (sum, carry)
+    }
+
+    /// Returns `(sum, carry)` of the inputs.
+    fn half_adder(&mut self, x: i32, y: i32) -> (i32, i32) {
+        let sum = self.new_var();
+        let carry = self.new_var();
+
+        self.clause([-x, -y, -sum]);
+        self.clause([x, y, -sum]);
+        self.clause([-x, y, sum]);
+        self.clause([x, -y, sum]);
+
+        self.clause([-x, -y, carry]);
+        self.clause([x, -carry]);
+        self.clause([y, -carry]);
+
+        (sum, carry)
+    }
+}
+
+impl Solver {
+    /// Create a solver for the constraints of an OPB instance. The objective function is ignored.
+    pub fn from_opb(input: &str, encoding: PbEncoding) -> Result<Solver, Box<dyn Error>> {
+        let instance = Opb::parse(input)?;
+
+        // Auxiliary variables of the encodings must not clash with the variables of the instance.
+        let mut solver = Solver::new();
+        while solver.num_vars() < instance.num_vars {
+            solver.new_var();
+        }
+
+        for constraint in &instance.constraints {
+            solver.add_pb_constraint(constraint, encoding)?;
+        }
+
+        Ok(solver)
+    }
+
+    /// Add a linear pseudo-Boolean constraint, which is encoded into clauses using `encoding`.
+    /// The auxiliary variables are allocated after [`Solver::num_vars`].
+    ///
+    /// Fails without adding any clauses, if normalizing the constraint overflows 64-bit integers.
+    pub fn add_pb_constraint(
+        &mut self,
+        constraint: &PbConstraint,
+        encoding: PbEncoding,
+    ) -> Result<(), Box<dyn Error>> {
+        let normalized = normalize(constraint)?;
+
+        for &(_, lit) in &constraint.terms {
+            while self.num_vars() < lit.unsigned_abs() as usize {
+                self.new_var();
+            }
+        }
+
+        for (terms, rhs) in normalized {
+            if rhs <= 0 {
+                continue;
+            }
+            let rhs = rhs as u64;
+
+            // Coefficients larger than rhs can't contribute more than rhs.
+            let terms: Vec<(u64, i32)> = terms
+                .into_iter()
+                .map(|(coefficient, lit)| (coefficient.min(rhs), lit))
+                .collect();
+
+            let mut encoder = Encoder { solver: self };
+            if terms.iter().fold(0u64, |sum, &(coefficient, _)| {
+                sum.saturating_add(coefficient)
+            }) < rhs
+            {
+                encoder.clause([]);
+            } else {
+                encoder.encode(&terms, rhs, encoding);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let opb = "
+        * #variable= 3 #constraint= 3
+        min: +1 x1 -2 x3 ;
+        +1 x1 +2 ~x2 >= 2 ;
+        -1 x2 +1 x3
+           = 0;
+        3 x1 2 x3 <= 4 ;
+        ";
+
+        let instance = Opb::parse(opb).unwrap();
+        assert_eq!(instance.num_vars, 3);
+        assert_eq!(instance.objective, Some(vec![(1, 1), (-2, 3)]));
+        assert_eq!(
+            instance.constraints,
+            vec![
+                PbConstraint {
+                    terms: vec![(1, 1), (2, -2)],
+                    relation: Relation::GreaterEq,
+                    rhs: 2
+                },
+                PbConstraint {
+                    terms: vec![(-1, 2), (1, 3)],
+                    relation: Relation::Eq,
+                    rhs: 0
+                },
+                PbConstraint {
+                    terms: vec![(3, 1), (2, 3)],
+                    relation: Relation::LessEq,
+                    rhs: 4
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Opb::parse("+1 x1 >= ;").is_err());
+        assert!(Opb::parse("+1 y1 >= 1 ;").is_err());
+        assert!(Opb::parse("+1 x1 +1 >= 1 ;").is_err());
+        assert!(Opb::parse("+1 x1 x2 >= 1 ;").is_err());
+    }
+
+    #[test]
+    fn normalize_negative_coefficients() {
+        let constraint = PbConstraint {
+            terms: vec![(2, 1), (-3, 2)],
+            relation: Relation::LessEq,
+            rhs: 1,
+        };
+
+        // -2 x1 + 3 x2 >= -1  <=>  2 ~x1 + 3 x2 >= 1
+        assert_eq!(
+            normalize(&constraint).unwrap(),
+            vec![(vec![(2, -1), (3, 2)], 1)]
+        );
+    }
+
+    #[test]
+    fn normalize_overflow() {
+        let constraint = PbConstraint {
+            terms: vec![(-1, 1)],
+            relation: Relation::GreaterEq,
+            rhs: i64::MAX,
+        };
+        assert!(normalize(&constraint).is_err());
+
+        let constraint = PbConstraint {
+            terms: vec![(i64::MIN, 1)],
+            relation: Relation::LessEq,
+            rhs: 0,
+        };
+        assert!(normalize(&constraint).is_err());
+
+        assert!(Solver::from_opb("-1 x1 >= 9223372036854775807 ;", PbEncoding::Bdd).is_err());
+    }
+}
@@ -0,0 +1,337 @@
+//! Portfolio solving with several differently configured solvers in parallel.
+//!
+//! Every thread solves the same formula with its own [`Config`]. Short learned clauses with a
+//! small glue value are published in a lock-free ring buffer, from which the other threads import
+//! them. The first thread with an answer terminates the others.
+
+use std::{
+    hint,
+    sync::{
+        atomic::{fence, AtomicI32, AtomicU32, AtomicU64, AtomicUsize, Ordering},
+        Arc, OnceLock,
+    },
+    thread,
+};
+
+use crate::{Config, DecisionHeuristic, Phase, Restarts, Result, Solver, TerminateHandle};
+
+/// Longest clause, which fits into a slot of the [`ClauseBuffer`].
+pub const MAX_SHARED_LEN: usize = 16;
+
+/// Number of slots of the [`ClauseBuffer`]. Readers, which fall behind by more, miss clauses.
+const BUFFER_CAPACITY: usize = 1 << 12;
+
+struct Slot {
+    /// Sequence lock: odd while the clause at position `(seq - 1) / 2` is written,
+    /// `2 * pos + 2` once the clause at position `pos` is complete.
+    seq: AtomicU64,
+    /// Index of the thread, which exported the clause.
+    source: AtomicUsize,
+    glue: AtomicU32,
+    len: AtomicUsize,
+    lits: [AtomicI32; MAX_SHARED_LEN],
+}
+
+impl Default for Slot {
+    fn default() -> Self {
+        Self {
+            seq: AtomicU64::new(0),
+            source: AtomicUsize::new(0),
+            glue: AtomicU32::new(0),
+            len: AtomicUsize::new(0),
+            lits: std::array::from_fn(|_| AtomicI32::new(0)),
+        }
+    }
+}
+
+/// Lock-free ring buffer of shared clauses. Writers claim consecutive positions and old clauses are
+/// overwritten, once the buffer wraps around.
+struct ClauseBuffer {
+    slots: Box<[Slot]>,
+    /// Next position to write.
+    head: AtomicU64,
+}
+
+impl ClauseBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity).map(|_| Slot::default()).collect(),
+            head: AtomicU64::new(0),
+        }
+    }
+
+    fn slot(&self, pos: u64) -> &Slot {
+        &self.slots[(pos % self.slots.len() as u64) as usize]
+    }
+
+    fn push(&self, source: usize, clause: &[i32], glue: u32) {
+        debug_assert!(clause.len() <= MAX_SHARED_LEN);
+
+        let pos = self.head.fetch_add(1, Ordering::Relaxed);
+        let slot = self.slot(pos);
+        loop {
+            let seq = slot.seq.load(Ordering::Relaxed);
+            if seq > 2 * pos {
+                // A writer, which wrapped around the buffer, already claimed the slot.
+                return;
+            }
+            if seq % 2 == 1 {
+                // The previous clause in this slot is still being written.
+                hint::spin_loop();
+                continue;
+            }
+            if slot
+                .seq
+                .compare_exchange_weak(seq, 2 * pos + 1, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+            {
+                break;
+            }
+        }
+        fence(Ordering::Release);
+
+        slot.source.store(source, Ordering::Relaxed);
+        slot.glue.store(glue, Ordering::Relaxed);
+        slot.len.store(clause.len(), Ordering::Relaxed);
+        for (dst, &lit) in slot.lits.iter().zip(clause) {
+            dst.store(lit, Ordering::Relaxed);
+        }
+
+        slot.seq.store(2 * pos + 2, Ordering::Release);
+    }
+
+    /// Pass the clauses after `cursor`, which weren't exported by `source`, to `import` and advance
+    /// the cursor. Stops at the first clause, which isn't complete yet.
+    fn read(&self, cursor: &mut u64, source: usize, mut import: impl FnMut(Vec<i32>, u32)) {
+        let head = self.head.load(Ordering::Relaxed);
+        *cursor = (*cursor).max(head.saturating_sub(self.slots.len() as u64));
+
+        while *cursor < head {
+            let pos = *cursor;
+            let slot = self.slot(pos);
+
+            let seq = slot.seq.load(Ordering::Acquire);
+            if seq < 2 * pos + 2 {
+                break;
+            }
+            *cursor += 1;
+            if seq > 2 * pos + 2 {
+                // Overwritten by a newer clause.
+                continue;
+            }
+
+            let clause_source = slot.source.load(Ordering::Relaxed);
+            let glue = slot.glue.load(Ordering::Relaxed);
+            let len = slot.len.load(Ordering::Relaxed).min(MAX_SHARED_LEN);
+            let clause: Vec<i32> = slot.lits[..len]
+                .iter()
+                .map(|lit| lit.load(Ordering::Relaxed))
+                .collect();
+
+            fence(Ordering::Acquire);
+            if slot.seq.load(Ordering::Relaxed) != seq || clause_source == source {
+                continue;
+            }
+            import(clause, glue);
+        }
+    }
+}
+
+/// Solves a formula with several solver instances in parallel threads.
+pub struct Portfolio {
+    configs: Vec<Config>,
+    max_shared_len: usize,
+    max_shared_glue: u32,
+    terminate_handle: TerminateHandle,
+}
+
+impl Portfolio {
+    /// Portfolio of `threads` solvers with diversified configurations.
+    pub fn new(threads: usize) -> Self {
+        let configs = (0..threads.max(1))
+            .map(|i| match i {
+                0 => Config {
+                    phase_saving: true,
+                    rephase_interval: Some(1000),
+                    mode_switch_interval: Some(1000),
+                    ..Config::default()
+                },
+                1 => Config {
+                    seed: 1,
+                    decision: DecisionHeuristic::Vsids,
+                    phase: Phase::Negative,
+                    phase_saving: true,
+                    restarts: Restarts::Luby { unit: 512 },
+                    ..Config::default()
+                },
+                2 => Config::default(),
+                _ => Config {
+                    seed: i as u64,
+                    decision: DecisionHeuristic::Vsids,
+                    phase: Phase::Random,
+                    phase_saving: i % 2 == 0,
+                    target_phases: i % 4 == 0,
+                    restarts: Restarts::Luby {
+                        unit: 64 << (i % 4),
+                    },
+                    rephase_interval: (i % 2 == 0).then_some(1000),
+                    mode_switch_interval: None,
+                },
+            })
+            .collect();
+        Self::with_configs(configs)
+    }
+
+    /// Portfolio with one thread per configuration. Panics on invalid configurations, see
+    /// [`Solver::set_config`].
+    pub fn with_configs(configs: Vec<Config>) -> Self {
+        assert!(!configs.is_empty(), "a portfolio needs at least one solver");
+        for config in &configs {
+            config.assert_valid();
+        }
+        Self {
+            configs,
+            max_shared_len: 8,
+            max_shared_glue: 4,
+            terminate_handle: TerminateHandle::default(),
+        }
+    }
+
+    pub fn configs(&self) -> &[Config] {
+        &self.configs
+    }
+
+    /// Share learned clauses with at most `max_len` literals and a glue value of at most `max_glue`.
+    /// Sharing is disabled with a length of zero. Clauses longer than [`MAX_SHARED_LEN`] are never
+    /// shared.
+    pub fn share(mut self, max_len: usize, max_glue: u32) -> Self {
+        self.max_shared_len = max_len.min(MAX_SHARED_LEN);
+        self.max_shared_glue = max_glue;
+        self
+    }
+
+    /// Get a handle which can be used to stop all solvers from another thread.
+    pub fn terminate_handle(&self) -> TerminateHandle {
+        self.terminate_handle.clone()
+    }
+
+    /// Solve `clauses` in parallel. The result is the answer of the first solver, which finishes,
+    /// or `Result::Unknown` if all of them were terminated.
+    pub fn solve(&self, clauses: &[Vec<i32>]) -> Result {
+        let buffer = Arc::new(ClauseBuffer::new(BUFFER_CAPACITY));
+        let answer = OnceLock::new();
+        let num_vars = clauses.iter().flatten().map(|lit| lit.abs()).max();
+        let num_vars = num_vars.unwrap_or(0);
+
+        let solvers: Vec<Solver> = self
+            .configs
+            .iter()
+            .enumerate()
+            .map(|(source, config)| {
+                let mut solver = Solver::with_config(config.clone());
+                while (solver.num_vars() as i32) < num_vars {
+                    solver.new_var();
+                }
+                for clause in clauses {
+                    solver.add_clause(clause.iter().copied());
+                }
+                self.connect(&mut solver, source, &buffer);
+                solver
+            })
+            .collect();
+        let handles: Vec<TerminateHandle> = solvers.iter().map(Solver::terminate_handle).collect();
+
+        thread::scope(|scope| {
+            for mut solver in solvers {
+                let (answer, handles) = (&answer, &handles);
+                scope.spawn(move || {
+                    let result = solver.solve();
+                    if !result.is_unknown() && answer.set(result).is_ok() {
+                        for handle in handles {
+                            handle.terminate();
+                        }
+                    }
+                });
+            }
+        });
+
+        answer.into_inner().unwrap_or(Result::Unknown)
+    }
+
+    /// Export the learned clauses of `solver` to the buffer and import those of the other solvers,
+    /// whenever it polls for termination.
+    fn connect(&self, solver: &mut Solver, source: usize, buffer: &Arc<ClauseBuffer>) {
+        if self.max_shared_len > 0 {
+            let export = buffer.clone();
+            solver.set_learn(
+                self.max_shared_len,
+                self.max_shared_glue,
+                move |clause, glue| export.push(source, clause, glue),
+            );
+        }
+
+        let import = buffer.clone();
+        let importer = solver.clause_importer();
+        let terminate_handle = self.terminate_handle.clone();
+        let mut cursor = 0;
+        solver.set_terminate(move || {
+            import.read(&mut cursor, source, |clause, glue| {
+                importer.import(clause, glue);
+            });
+            terminate_handle.is_terminated()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(buffer: &ClauseBuffer, cursor: &mut u64, source: usize) -> Vec<Vec<i32>> {
+        let mut clauses = Vec::new();
+        buffer.read(cursor, source, |clause, _| clauses.push(clause));
+        clauses
+    }
+
+    #[test]
+    fn buffer_skips_own_and_overwritten_clauses() {
+        let buffer = ClauseBuffer::new(4);
+        let mut cursor = 0;
+
+        buffer.push(0, &[1, 2], 2);
+        buffer.push(1, &[-3], 1);
+        assert_eq!(drain(&buffer, &mut cursor, 0), [vec![-3]]);
+        assert_eq!(drain(&buffer, &mut cursor, 0), Vec::<Vec<i32>>::new());
+
+        for i in 1..=6 {
+            buffer.push(1, &[i, -i], 2);
+        }
+        // Only the last four clauses are still in the buffer.
+        assert_eq!(
+            drain(&buffer, &mut cursor, 0),
+            [vec![3, -3], vec![4, -4], vec![5, -5], vec![6, -6]]
+        );
+    }
+
+    #[test]
+    fn concurrent_writers() {
+        let buffer = ClauseBuffer::new(64);
+        thread::scope(|scope| {
+            for source in 0..4 {
+                let buffer = &buffer;
+                scope.spawn(move || {
+                    for i in 0..1000 {
+                        let lit = source as i32 * 1000 + i + 1;
+                        buffer.push(source, &[lit, lit], 1);
+                    }
+                });
+            }
+        });
+
+        let mut cursor = 0;
+        let clauses = drain(&buffer, &mut cursor, 4);
+        assert!(!clauses.is_empty() && clauses.len() <= 64);
+        // A torn read would mix literals of different clauses.
+        assert!(clauses.iter().all(|clause| clause[0] == clause[1]));
+    }
+}
@@ -6,7 +6,7 @@ use super::{
     clause::{Clause, ClauseIdx},
     data::VarVec,
     trail::{Trail, TrailReason},
-    Lit, Solver,
+    Lit, Solver, Var,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -30,6 +30,9 @@ pub(crate) struct AnalyzeState {
 
     /// Levels in new clause. We use this to derive the LDB value of a new clause.
     levels_in_clause: Vec<u32>,
+
+    /// Variables marked in `seen`, whose activity is bumped after the analysis.
+    seen_vars: Vec<Var>,
 }
 
 impl AnalyzeState {
@@ -40,6 +43,7 @@ impl AnalyzeState {
         self.levels_in_clause.clear();
         self.levels_seen.clear();
         self.levels_seen.resize(decision_levels + 1, false);
+        self.seen_vars.clear();
         self.open = 0;
     }
 
@@ -81,6 +85,7 @@ impl AnalyzeState {
         }
 
         self.seen[lit.var()] = true;
+        self.seen_vars.push(lit.var());
     }
 
     fn has_seen_lit(&self, lit: Lit) -> bool {
@@ -140,11 +145,18 @@ impl Solver {
             return AnalyzeResult::Unsat;
         }
 
+        // Everything before the current decision was propagated without a conflict.
+        let conflict_free = self.trail.last_decision_pos().unwrap();
+        self.decider
+            .update_target_phases(&self.trail.trail()[..conflict_free]);
+
         let mut trail_pos = self.trail.assigned_vars();
         let mut reason = conflict_clause;
         let mut maybe_uip = None;
 
-        let mut analyze_state = &mut self.analyze_state;
+        // Reason clauses might be created lazily during the analysis, which requires mutable access to the
+        // solver. Therefore, we temporarily take the analyze state out of the solver.
+        let mut analyze_state = std::mem::take(&mut self.analyze_state);
         analyze_state.reset(self.trail.total_vars(), current_level as usize);
 
         // Determine new 1UIP clause
@@ -173,7 +185,7 @@ impl Solver {
             }
             analyze_state.open -= 1;
 
-            let reason_idx = self.trail.get_reason_cls(uip);
+            let reason_idx = self.reason_clause(uip);
             reason = self.clause_db.get(reason_idx);
 
             debug!(
@@ -201,26 +213,42 @@ impl Solver {
             .max()
             .unwrap_or(0);
 
-        self.unpropagated_lit_pos = self.trail.backtrack(backjump_level, |trail_elem| {
-            if let TrailReason::Propagated { cls } = trail_elem.reason {
-                self.clause_db.get_mut(cls).flags().set_is_reason(false);
-            }
-        });
+        self.backtrack(backjump_level);
+
+        self.decider.bump_conflict(&mut analyze_state.seen_vars);
 
         if uip_clause.len() == 1 {
             debug_assert_eq!(backjump_level, 0);
+            self.restart_state.learned(1);
+            if let Some(learn_callback) = &mut self.learn_callback {
+                learn_callback.export(uip_clause, 1);
+            }
             self.trail.assign_lit(-uip, TrailReason::Axiom);
         } else {
-            let ldb_glue = (analyze_state.levels_in_clause.len() as u32).try_into()
+            let ldb_glue: NonZeroU32 = (analyze_state.levels_in_clause.len() as u32).try_into()
                 .expect("There has to be atleast a one level in clause, otherwise the clause length would be one");
             debug_assert_eq!(
                 ldb_glue,
-                Self::calculate_ldb_from_lits(&self.trail, &uip_clause)
+                Self::calculate_ldb_from_lits(&self.trail, uip_clause)
             );
             debug!("new 1UIP clause has ldb value of {ldb_glue}");
+            self.restart_state.learned(ldb_glue.get());
 
-            let uip_clause_idx = self.clause_db.insert_clause(&uip_clause, Some(ldb_glue));
-            debug_assert!(self.trail.are_lits_all_unassigned(&uip_clause));
+            if let Some(learn_callback) = &mut self.learn_callback {
+                learn_callback.export(uip_clause, ldb_glue.get());
+            }
+
+            // Watch the flipped uip and a literal of the backjump level. They are assigned last,
+            // so they are the first literals to become unassigned when backtracking further.
+            let last = uip_clause.len() - 1;
+            uip_clause.swap(0, last);
+            let backjump_lit_idx = (1..uip_clause.len())
+                .find(|&idx| self.trail.get_decision_level(uip_clause[idx]) == Some(backjump_level))
+                .unwrap();
+            uip_clause.swap(1, backjump_lit_idx);
+
+            let uip_clause_idx = self.attach_clause(uip_clause, Some(ldb_glue));
+            debug_assert!(self.trail.are_lits_all_unassigned(uip_clause));
             debug!(
                 "Assigning flipped uip {} because of learned driving clause {uip_clause:?}",
                 -uip
@@ -237,10 +265,97 @@ impl Solver {
                 .set_is_reason(true);
         }
 
+        self.analyze_state = analyze_state;
+
         AnalyzeResult::Done
     }
 
-    fn calculate_ldb_from_lits(trail: &Trail, cls: &[Lit]) -> NonZeroU32 {
+    /// Determine the assumptions responsible for falsifying the assumption `lit`.
+    /// Because assumptions are decided before any other variable, every decision in the
+    /// implication graph of `-lit` is an assumption.
+    pub(crate) fn analyze_final(&mut self, lit: Lit) {
+        debug_assert!(self.trail.is_lit_unsatisfied(lit));
+
+        self.failed_assumptions.clear();
+        self.failed_assumptions.push(lit.into());
+
+        let mut analyze_state = std::mem::take(&mut self.analyze_state);
+        analyze_state.reset(
+            self.trail.total_vars(),
+            self.trail.current_decision_level() as usize,
+        );
+        analyze_state.seen[lit.var()] = true;
+
+        for trail_pos in (0..self.trail.assigned_vars()).rev() {
+            let trail_elem = *self.trail.get(trail_pos).unwrap();
+            if !analyze_state.seen[trail_elem.lit.var()] {
+                continue;
+            }
+            // Literals on decision level zero don't depend on any assumption.
+            if self.trail.get_decision_level(trail_elem.lit) == Some(0) {
+                break;
+            }
+
+            match trail_elem.reason {
+                TrailReason::Decision => self.failed_assumptions.push(trail_elem.lit.into()),
+                TrailReason::Axiom => {}
+                _ => {
+                    let reason_idx = self.reason_clause(trail_elem.lit);
+                    for other_lit in self.clause_db.get(reason_idx) {
+                        if self.trail.get_decision_level(other_lit) != Some(0) {
+                            analyze_state.seen[other_lit.var()] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.analyze_state = analyze_state;
+        debug!("failed assumptions: {:?}", self.failed_assumptions);
+    }
+
+    /// Get the reason clause of the propagated literal `lit`.
+    /// Literals which weren't propagated by a clause get their reason clause created on demand.
+    pub(crate) fn reason_clause(&mut self, lit: Lit) -> ClauseIdx {
+        match self.trail.get_reason(lit) {
+            TrailReason::Propagated { cls } => cls,
+            TrailReason::External => {
+                let reason = self.external_reason(lit);
+                self.add_reason_clause(lit, reason)
+            }
+            TrailReason::Xor => {
+                let reason = self.xor_reason(lit);
+                self.add_reason_clause(lit, reason)
+            }
+            TrailReason::Cardinality => {
+                let reason = self.cardinality_reason(lit);
+                self.add_reason_clause(lit, reason)
+            }
+            TrailReason::Decision | TrailReason::Axiom => {
+                panic!("Literal {lit} wasn't propagated")
+            }
+        }
+    }
+
+    /// Add the lazily created reason clause of `lit` to the clause database and use it as reason on the trail.
+    fn add_reason_clause(&mut self, lit: Lit, mut reason: Vec<Lit>) -> ClauseIdx {
+        debug!("adding reason clause {reason:?} for {lit}");
+        debug_assert!(reason.contains(&lit));
+        debug_assert!(reason
+            .iter()
+            .all(|&other| other == lit || self.trail.is_lit_unsatisfied(other)));
+
+        let glue = Self::calculate_ldb_from_lits(&self.trail, &reason);
+        self.order_for_watching(&mut reason);
+        let cls_idx = self.attach_clause(&reason, Some(glue));
+
+        self.clause_db.get_mut(cls_idx).flags().set_is_reason(true);
+        self.trail
+            .set_reason(lit, TrailReason::Propagated { cls: cls_idx });
+        cls_idx
+    }
+
+    pub(crate) fn calculate_ldb_from_lits(trail: &Trail, cls: &[Lit]) -> NonZeroU32 {
         (cls.iter()
             .map(|&lit| trail.get_decision_level(lit))
             .collect::<std::collections::HashSet<_>>()
@@ -249,3 +364,41 @@ impl Solver {
             .unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::propagate::PropagationResult;
+
+    fn decide_and_propagate(solver: &mut Solver, lit: i32) -> PropagationResult {
+        solver
+            .trail
+            .assign_lit(Lit::new(lit), TrailReason::Decision);
+        solver.propagate()
+    }
+
+    #[test]
+    fn learned_clause_propagates_after_backtracking() {
+        let mut solver = Solver::new();
+        solver.add_clause([-1, -2, 3]);
+        solver.add_clause([-1, -2, -3]);
+
+        assert_eq!(
+            decide_and_propagate(&mut solver, 1),
+            PropagationResult::Done
+        );
+        let PropagationResult::Contradiction(conflict) = decide_and_propagate(&mut solver, 2)
+        else {
+            panic!("deciding 1 and 2 is contradictory");
+        };
+        assert_eq!(solver.analyze_contradiction(conflict), AnalyzeResult::Done);
+
+        // The learned clause (-1 -2) has to imply -2, once 1 is decided again.
+        solver.backtrack(0);
+        assert_eq!(
+            decide_and_propagate(&mut solver, 1),
+            PropagationResult::Done
+        );
+        assert!(solver.trail.is_lit_satisfied(Lit::new(-2)));
+    }
+}
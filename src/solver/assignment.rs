@@ -5,6 +5,8 @@ pub(crate) struct AssignData {
     pub status: bool,
     pub decision_level: u32,
     pub reason: TrailReason,
+    /// Position of the literal on the trail.
+    pub trail_pos: usize,
 }
 
 #[derive(Default)]
@@ -43,13 +45,20 @@ impl Assignment {
         }
     }
 
-    pub fn assign_lit(&mut self, lit: Lit, decision_level: u32, reason: TrailReason) {
+    pub fn assign_lit(
+        &mut self,
+        lit: Lit,
+        decision_level: u32,
+        reason: TrailReason,
+        trail_pos: usize,
+    ) {
         debug_assert!(self.is_lit_unassigned(lit));
 
         self.assignment[lit.var()] = Some(AssignData {
             status: lit.is_pos(),
             decision_level,
             reason,
+            trail_pos,
         });
     }
 
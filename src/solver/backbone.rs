@@ -0,0 +1,62 @@
+use super::{Lit, Result, Solver};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackboneResult {
+    /// The literals, which are true in every model, in ascending variable order.
+    Backbone(Vec<i32>),
+    Unsat,
+    /// The solver gave up, because the [`Budget`](super::Budget) was exhausted or termination
+    /// was requested.
+    Unknown,
+}
+
+impl Solver {
+    /// Compute the backbone of the formula, i.e. the literals, which are true in every model.
+    ///
+    /// The literals of a first model are the candidates. Each remaining candidate is tested by
+    /// assuming its negation. A model found this way drops all candidates it falsifies, a failed
+    /// test adds the candidate as unit clause. Learned clauses are kept between the tests and
+    /// the progress is counted in [`Solver::stats`].
+    pub fn backbone(&mut self) -> BackboneResult {
+        let model = match self.solve() {
+            Result::Sat(model) => model,
+            Result::Unsat(_) => return BackboneResult::Unsat,
+            Result::Unknown => return BackboneResult::Unknown,
+        };
+
+        // Candidates are indexed by their variable.
+        let mut candidates: Vec<Option<i32>> = model.iter().map(Some).collect();
+        for idx in 0..candidates.len() {
+            let Some(lit) = candidates[idx] else {
+                continue;
+            };
+
+            // Literals fixed on decision level zero need no test.
+            let fixed = Lit::new(lit);
+            if self.trail.get_decision_level(fixed) == Some(0) && self.trail.is_lit_satisfied(fixed)
+            {
+                self.stats.backbone_literals += 1;
+                continue;
+            }
+
+            self.stats.backbone_checks += 1;
+            match self.solve_with_assumptions(&[-lit]) {
+                Result::Sat(model) => {
+                    for candidate in &mut candidates[idx..] {
+                        if candidate.is_some_and(|lit| !model.lit(lit)) {
+                            *candidate = None;
+                            self.stats.backbone_filtered += 1;
+                        }
+                    }
+                }
+                Result::Unsat(_) => {
+                    self.stats.backbone_literals += 1;
+                    self.add_clause([lit]);
+                }
+                Result::Unknown => return BackboneResult::Unknown,
+            }
+        }
+
+        BackboneResult::Backbone(candidates.into_iter().flatten().collect())
+    }
+}
@@ -0,0 +1,265 @@
+use tracing::debug;
+
+use super::{
+    data::{LitVec, VarVec},
+    propagate::ConstraintPropagationResult,
+    trail::{Trail, TrailReason},
+    Lit, Solver,
+};
+
+/// At most `k` of the literals may be true.
+#[derive(Debug, Clone)]
+struct AtMostK {
+    lits: Vec<Lit>,
+    k: usize,
+}
+
+/// Storage for cardinality constraints, which are propagated by counting the satisfied literals of
+/// every constraint.
+#[derive(Default)]
+pub(crate) struct CardinalityDB {
+    constraints: Vec<AtMostK>,
+
+    /// Number of counted satisfied literals for each constraint.
+    counts: Vec<usize>,
+
+    /// Constraints in which a literal occurs.
+    occurrences: LitVec<Vec<usize>>,
+
+    /// Position on the trail up to which literals were counted.
+    counted_pos: usize,
+
+    /// Trail position of each counted variable. `None` if the variable isn't counted.
+    trail_pos: VarVec<Option<usize>>,
+
+    /// Constraint which implied the assignment of a variable, together with the trail position
+    /// of the literal that triggered the propagation.
+    reasons: VarVec<(usize, usize)>,
+}
+
+impl CardinalityDB {
+    fn add(&mut self, constraint: AtMostK) {
+        let idx = self.constraints.len();
+        for &lit in &constraint.lits {
+            self.occurrences.expand(-Lit::from(lit.var()), Vec::new());
+            self.occurrences[lit].push(idx);
+            self.trail_pos.expand(lit.var(), None);
+            self.reasons.expand(lit.var(), (0, 0));
+        }
+
+        self.constraints.push(constraint);
+        self.counts.push(0);
+
+        // Count the satisfied literals of the new constraint, by counting the whole trail again.
+        self.counts.fill(0);
+        self.trail_pos.fill(None);
+        self.counted_pos = 0;
+    }
+
+    /// Called for every literal removed from the trail during backtracking.
+    pub(crate) fn unassign(&mut self, lit: Lit) {
+        if lit.var().get() as usize > self.trail_pos.len()
+            || self.trail_pos[lit.var()].take().is_none()
+        {
+            return;
+        }
+
+        for &idx in &self.occurrences[lit] {
+            self.counts[idx] -= 1;
+        }
+    }
+
+    pub(crate) fn backtrack(&mut self, trail_len: usize) {
+        self.counted_pos = self.counted_pos.min(trail_len);
+    }
+
+    /// Satisfied literals of the constraint `idx`, which were counted up to trail position `pos`.
+    fn counted_lits<'a>(
+        &'a self,
+        idx: usize,
+        pos: usize,
+        trail: &'a Trail,
+    ) -> impl Iterator<Item = Lit> + 'a {
+        self.constraints[idx]
+            .lits
+            .iter()
+            .copied()
+            .filter(move |&lit| trail.is_lit_satisfied(lit))
+            .filter(move |&lit| self.trail_pos[lit.var()].is_some_and(|lit_pos| lit_pos <= pos))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    pub(crate) fn is_satisfied(&self, trail: &Trail) -> bool {
+        self.constraints.iter().all(|constraint| {
+            constraint
+                .lits
+                .iter()
+                .filter(|&&lit| trail.is_lit_satisfied(lit))
+                .count()
+                <= constraint.k
+        })
+    }
+}
+
+impl Solver {
+    /// Add the constraint, that at most `k` of the literals `lits` are true.
+    /// The literals are treated as a set, duplicates are ignored.
+    pub fn add_at_most_k(&mut self, lits: &[i32], k: usize) {
+        self.backtrack(0);
+
+        let mut lits: Vec<Lit> = lits.iter().copied().map(Lit::new).collect();
+        lits.sort_by_key(|lit| lit.get());
+        lits.dedup();
+
+        for &lit in &lits {
+            self.trail.expand(lit.var());
+            self.watches.expand(-Lit::from(lit.var()), Vec::new());
+        }
+
+        // Exactly one of two complementary literals is true.
+        let mut k = k;
+        let mut remaining = Vec::with_capacity(lits.len());
+        for lit in lits {
+            if remaining.last() == Some(&-lit) {
+                remaining.pop();
+                if k == 0 {
+                    self.integrate_clause(Vec::new(), None);
+                    return;
+                }
+                k -= 1;
+            } else {
+                remaining.push(lit);
+            }
+        }
+        let lits = remaining;
+
+        if k >= lits.len() {
+            return;
+        }
+
+        if k == 0 {
+            for lit in lits {
+                self.integrate_clause(vec![-lit], None);
+            }
+            return;
+        }
+
+        self.cardinality_db.add(AtMostK { lits, k });
+    }
+
+    /// Add the constraint, that at least `k` of the literals `lits` are true.
+    /// The literals are treated as a set, duplicates are ignored.
+    pub fn add_at_least_k(&mut self, lits: &[i32], k: usize) {
+        let mut lits = lits.to_vec();
+        lits.sort();
+        lits.dedup();
+
+        if k > lits.len() {
+            self.backtrack(0);
+            self.integrate_clause(Vec::new(), None);
+            return;
+        }
+
+        // At least k literals are true, iff at most n - k negated literals are true.
+        let negated: Vec<i32> = lits.iter().map(|&lit| -lit).collect();
+        self.add_at_most_k(&negated, lits.len() - k);
+    }
+
+    /// Count the literals assigned since the last call and propagate constraints, whose limit is reached.
+    pub(crate) fn propagate_cardinality(&mut self) -> ConstraintPropagationResult {
+        if self.cardinality_db.constraints.is_empty() {
+            return ConstraintPropagationResult::Fixpoint;
+        }
+
+        let mut result = ConstraintPropagationResult::Fixpoint;
+
+        while let Some(&trail_elem) = self.trail.get(self.cardinality_db.counted_pos) {
+            let pos = self.cardinality_db.counted_pos;
+            self.cardinality_db.counted_pos += 1;
+
+            let lit = trail_elem.lit;
+            let db = &mut self.cardinality_db;
+            db.trail_pos.expand(lit.var(), None);
+            db.trail_pos[lit.var()] = Some(pos);
+            db.occurrences.expand(-Lit::from(lit.var()), Vec::new());
+            for &idx in &db.occurrences[lit] {
+                db.counts[idx] += 1;
+            }
+
+            for occurrence in 0..self.cardinality_db.occurrences[lit].len() {
+                let idx = self.cardinality_db.occurrences[lit][occurrence];
+                let count = self.cardinality_db.counts[idx];
+                let k = self.cardinality_db.constraints[idx].k;
+
+                if count > k {
+                    let conflict: Vec<Lit> = self
+                        .cardinality_db
+                        .counted_lits(idx, pos, &self.trail)
+                        .map(|lit| -lit)
+                        .collect();
+                    debug!("cardinality constraint {idx} is falsified {conflict:?}");
+                    return self.add_conflict_clause(conflict);
+                }
+
+                if count == k {
+                    for other in 0..self.cardinality_db.constraints[idx].lits.len() {
+                        let other = self.cardinality_db.constraints[idx].lits[other];
+                        if !self.trail.is_lit_unassigned(other) {
+                            continue;
+                        }
+
+                        debug!("cardinality constraint {idx} propagates {}", -other);
+                        self.cardinality_db.reasons[other.var()] = (idx, pos);
+                        self.trail.assign_lit(-other, TrailReason::Cardinality);
+                        self.stats.propagations += 1;
+                        result = ConstraintPropagationResult::Changed;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Reason clause of the literal `lit` propagated by a cardinality constraint.
+    pub(crate) fn cardinality_reason(&self, lit: Lit) -> Vec<Lit> {
+        let (idx, pos) = self.cardinality_db.reasons[lit.var()];
+
+        let mut reason = vec![lit];
+        reason.extend(
+            self.cardinality_db
+                .counted_lits(idx, pos, &self.trail)
+                .map(|counted| -counted),
+        );
+        reason
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Solver;
+
+    #[test]
+    fn propagation_without_decisions() {
+        let mut solver = Solver::new();
+        solver.add_at_most_k(&[1, 2, 3, 4], 2);
+        solver.add_clause([1]);
+        solver.add_clause([3, 5]);
+        solver.add_clause([2]);
+
+        let model = solver.solve().unwrap_sat();
+        assert_eq!(model.as_vec(), vec![1, 2, -3, -4, 5]);
+        assert_eq!(solver.stats().decisions, 0);
+    }
+
+    #[test]
+    fn complementary_literals() {
+        let mut solver = Solver::new();
+        solver.add_at_most_k(&[1, -1, 2], 1);
+        solver.add_clause([2]);
+        assert!(solver.solve().is_unsat());
+    }
+}
@@ -0,0 +1,103 @@
+use super::Solver;
+
+/// How the next decision variable is chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecisionHeuristic {
+    /// The unassigned variable with the smallest index.
+    #[default]
+    FirstUnassigned,
+    /// The unassigned variable with the highest activity, which is bumped for every variable
+    /// involved in a conflict and decays over time.
+    Vsids,
+    /// Variable move-to-front: the unassigned variable, which was involved in a conflict most
+    /// recently.
+    Vmtf,
+}
+
+/// Polarity of decisions on variables without a saved phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Phase {
+    #[default]
+    Positive,
+    Negative,
+    /// Random polarities drawn from the seed of the [`Config`].
+    Random,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Restarts {
+    #[default]
+    Never,
+    /// Restart after `unit` times the next element of the Luby sequence (1, 1, 2, 1, 1, 2, 4, ...)
+    /// conflicts.
+    Luby { unit: u64 },
+    /// Restart when the glue of the recently learned clauses is high compared to the long term
+    /// average, as in Glucose.
+    Glucose,
+}
+
+/// Search heuristics of a [`Solver`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    /// Seed for random decisions. Unless it is zero, it also perturbs the initial VSIDS order.
+    pub seed: u64,
+    pub decision: DecisionHeuristic,
+    pub phase: Phase,
+    /// Decide variables with the polarity they had, when they were last unassigned.
+    pub phase_saving: bool,
+    pub restarts: Restarts,
+    /// Decide variables with the polarity they had on the largest conflict-free trail since the
+    /// last restart, before falling back to the saved phase.
+    pub target_phases: bool,
+    /// Overwrite the saved phases after this many conflicts. The gaps grow arithmetically, the
+    /// `n`-th rephasing happens `n` times this many conflicts after the previous one.
+    /// The new phases cycle through the original, inverted, best and local search phases.
+    pub rephase_interval: Option<u64>,
+    /// Alternate between a focused and a stable [`SearchMode`](super::SearchMode). The first
+    /// focused phase lasts this many conflicts and every following phase is twice as long as the
+    /// previous one. While enabled, the mode determines `decision`, `target_phases` and `restarts`.
+    pub mode_switch_interval: Option<u64>,
+}
+
+impl Config {
+    /// Panics if a schedule of the configuration would never advance.
+    pub(crate) fn assert_valid(&self) {
+        assert!(
+            self.restarts != Restarts::Luby { unit: 0 },
+            "the Luby restart unit must be positive"
+        );
+        assert_ne!(
+            self.rephase_interval,
+            Some(0),
+            "the rephasing interval must be positive"
+        );
+        assert_ne!(
+            self.mode_switch_interval,
+            Some(0),
+            "the mode switch interval must be positive"
+        );
+    }
+}
+
+impl Solver {
+    /// Panics if the Luby restart unit, the rephasing interval or the mode switch interval is
+    /// zero.
+    pub fn with_config(config: Config) -> Self {
+        let mut solver = Self::default();
+        solver.set_config(config);
+        solver
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Replace the search heuristics. Saved phases and activities are kept.
+    /// Panics if the Luby restart unit, the rephasing interval or the mode switch interval is
+    /// zero.
+    pub fn set_config(&mut self, config: Config) {
+        config.assert_valid();
+        self.decider.seed(config.seed);
+        self.config = config;
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::{HashMap, HashSet};
+
+use tracing::debug;
+
+use super::{propagate::PropagationResult, trail::TrailReason, Lit, Result, Solver, Var};
+use crate::BigUint;
+
+/// State of a model counting run, see [`Solver::count_projected_models`].
+struct Counter {
+    /// Whether a variable, indexed by its number, is counted.
+    projected: Vec<bool>,
+    /// Counts of the already solved components, keyed by their clauses.
+    cache: HashMap<Vec<u32>, BigUint>,
+}
+
+impl Counter {
+    fn is_projected(&self, var: Var) -> bool {
+        self.projected[var.get() as usize]
+    }
+}
+
+/// Split `clauses` into components, which don't share variables.
+fn components(clauses: Vec<Vec<Lit>>) -> Vec<Vec<Vec<Lit>>> {
+    let mut parent: HashMap<Var, Var> = HashMap::new();
+    fn find(parent: &mut HashMap<Var, Var>, var: Var) -> Var {
+        let mut root = var;
+        while let Some(&next) = parent.get(&root).filter(|&&next| next != root) {
+            root = next;
+        }
+        parent.insert(var, root);
+        root
+    }
+
+    for clause in &clauses {
+        let first = find(&mut parent, clause[0].var());
+        for lit in &clause[1..] {
+            let root = find(&mut parent, lit.var());
+            parent.insert(root, first);
+        }
+    }
+
+    let mut components: HashMap<Var, Vec<Vec<Lit>>> = HashMap::new();
+    for clause in clauses {
+        let root = find(&mut parent, clause[0].var());
+        components.entry(root).or_default().push(clause);
+    }
+    components.into_values().collect()
+}
+
+/// Cache key of a component, i.e. its sorted clauses separated by `u32::MAX`.
+fn cache_key(clauses: &[Vec<Lit>]) -> Vec<u32> {
+    let mut sorted: Vec<&[Lit]> = clauses.iter().map(Vec::as_slice).collect();
+    sorted.sort_unstable_by(|a, b| {
+        Lit::lit_slice_to_u32_slice(a).cmp(Lit::lit_slice_to_u32_slice(b))
+    });
+
+    let mut key = Vec::new();
+    for clause in sorted {
+        key.extend_from_slice(Lit::lit_slice_to_u32_slice(clause));
+        key.push(u32::MAX);
+    }
+    key
+}
+
+impl Solver {
+    /// Count the models of the formula exactly.
+    /// Returns `None`, if the [`Budget`](super::Budget) of the initial satisfiability check was
+    /// exhausted or termination was requested before the count was complete.
+    ///
+    /// Panics if the solver has xor or cardinality constraints or a user propagator.
+    pub fn count_models(&mut self) -> Option<BigUint> {
+        let projection: Vec<i32> = (1..=self.num_vars() as i32).collect();
+        self.count_projected_models(&projection)
+    }
+
+    /// Count the assignments of the variables in `projection`, which can be extended to a model.
+    /// Returns `None`, if the [`Budget`](super::Budget) of the initial satisfiability check was
+    /// exhausted or termination was requested before the count was complete.
+    ///
+    /// The search is a DPLL-style branching over the projection variables. The remaining clauses
+    /// are split into independent components after every decision, whose counts are multiplied
+    /// and cached.
+    ///
+    /// Panics if the solver has xor or cardinality constraints or a user propagator.
+    pub fn count_projected_models(&mut self, projection: &[i32]) -> Option<BigUint> {
+        assert!(
+            self.xor_db.is_empty() && self.cardinality_db.is_empty(),
+            "model counting only supports clauses"
+        );
+        assert!(
+            self.user_propagator.is_none(),
+            "model counting doesn't support a user propagator"
+        );
+
+        // The search doesn't learn clauses, so unsat formulas are ruled out with CDCL first.
+        match self.solve() {
+            Result::Sat(_) => {}
+            Result::Unsat(_) => return Some(BigUint::zero()),
+            Result::Unknown => return None,
+        }
+
+        self.backtrack(0);
+        for &lit in projection {
+            let var = Lit::new(lit).var();
+            self.trail.expand(var);
+            self.watches.expand(-Lit::from(var), Vec::new());
+        }
+
+        if self.trivially_unsat || !self.import_clauses() {
+            return Some(BigUint::zero());
+        }
+        match self.propagate() {
+            PropagationResult::Done => {}
+            PropagationResult::Interrupted => return None,
+            PropagationResult::Contradiction(_) | PropagationResult::Unsat => {
+                self.trivially_unsat = true;
+                return Some(BigUint::zero());
+            }
+        }
+
+        let mut projected = vec![false; self.num_vars() + 1];
+        for &lit in projection {
+            projected[lit.unsigned_abs() as usize] = true;
+        }
+        let clauses: Vec<Vec<Lit>> = self
+            .clause_db
+            .iter()
+            .filter(|clause| clause.glue().is_none())
+            .map(|clause| {
+                let mut lits = clause.lits().to_vec();
+                lits.sort_unstable_by_key(|lit| lit.get());
+                lits
+            })
+            .collect();
+        let mut counter = Counter {
+            projected,
+            cache: HashMap::new(),
+        };
+
+        let residual = self.residual(&clauses);
+        let vars = (1..=self.num_vars() as i32).map(Var::new);
+        let free = self.free_projected_vars(&counter, vars, &residual);
+        let count = self.count_formula(&mut counter, residual);
+        debug!(
+            "counted models with {} cached components",
+            counter.cache.len()
+        );
+
+        self.backtrack(0);
+        count.map(|count| count << free)
+    }
+
+    /// The clauses, which are not satisfied yet, without their falsified literals.
+    fn residual(&self, clauses: &[Vec<Lit>]) -> Vec<Vec<Lit>> {
+        clauses
+            .iter()
+            .filter(|clause| !clause.iter().any(|&lit| self.trail.is_lit_satisfied(lit)))
+            .map(|clause| {
+                clause
+                    .iter()
+                    .copied()
+                    .filter(|&lit| self.trail.is_lit_unassigned(lit))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Number of unassigned projection variables among `vars`, which don't occur in `residual`.
+    /// Each of them doubles the count.
+    fn free_projected_vars(
+        &self,
+        counter: &Counter,
+        vars: impl IntoIterator<Item = Var>,
+        residual: &[Vec<Lit>],
+    ) -> usize {
+        let occurring: HashSet<Var> = residual.iter().flatten().map(|lit| lit.var()).collect();
+        let free: HashSet<Var> = vars
+            .into_iter()
+            .filter(|&var| counter.is_projected(var) && !occurring.contains(&var))
+            .filter(|&var| self.trail.is_lit_unassigned(Lit::from(var)))
+            .collect();
+        free.len()
+    }
+
+    /// Count the assignments of the projection variables in `clauses`.
+    fn count_formula(&mut self, counter: &mut Counter, clauses: Vec<Vec<Lit>>) -> Option<BigUint> {
+        let mut count = BigUint::one();
+        for component in components(clauses) {
+            count = &count * &self.count_component(counter, component)?;
+            if count.is_zero() {
+                break;
+            }
+        }
+        Some(count)
+    }
+
+    /// Count the assignments of the projection variables in the connected `clauses`.
+    /// Components without projection variables are only checked for satisfiability.
+    fn count_component(
+        &mut self,
+        counter: &mut Counter,
+        clauses: Vec<Vec<Lit>>,
+    ) -> Option<BigUint> {
+        let key = cache_key(&clauses);
+        if let Some(count) = counter.cache.get(&key) {
+            return Some(count.clone());
+        }
+        if self.terminate_requested() {
+            return None;
+        }
+
+        // Branch on the most frequent variable, preferring projection variables.
+        let mut occurrences: HashMap<Var, usize> = HashMap::new();
+        for lit in clauses.iter().flatten() {
+            *occurrences.entry(lit.var()).or_default() += 1;
+        }
+        let (var, _) = occurrences
+            .iter()
+            .max_by_key(|&(&var, &occurrences)| (counter.is_projected(var), occurrences, var.get()))
+            .unwrap();
+        let (var, existential) = (*var, !counter.is_projected(*var));
+
+        let level = self.trail.current_decision_level();
+        let mut count = BigUint::zero();
+        for lit in [Lit::from(var), -Lit::from(var)] {
+            self.stats.decisions += 1;
+            self.trail.assign_lit(lit, TrailReason::Decision);
+
+            let branch_count = match self.propagate() {
+                PropagationResult::Done => {
+                    let residual = self.residual(&clauses);
+                    let free = self.free_projected_vars(
+                        counter,
+                        clauses.iter().flatten().map(|lit| lit.var()),
+                        &residual,
+                    );
+                    self.count_formula(counter, residual)
+                        .map(|count| count << free)
+                }
+                PropagationResult::Contradiction(_) | PropagationResult::Unsat => {
+                    Some(BigUint::zero())
+                }
+                PropagationResult::Interrupted => None,
+            };
+            self.backtrack(level);
+
+            count += &branch_count?;
+            if existential && !count.is_zero() {
+                break;
+            }
+        }
+
+        counter.cache.insert(key, count.clone());
+        Some(count)
+    }
+}
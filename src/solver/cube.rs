@@ -0,0 +1,174 @@
+use super::{propagate::PropagationResult, trail::TrailReason, Lit, Proof, Result, Solver, Var};
+
+/// Outcome of looking ahead on a single literal.
+enum Probe {
+    /// Propagating the literal assigned that many further literals.
+    Propagated(usize),
+    Conflict,
+    Interrupted,
+}
+
+/// Interruption of the cube generation.
+struct Interrupted;
+
+impl Solver {
+    /// Split the formula into at most `2^max_depth` cubes for cube-and-conquer.
+    ///
+    /// Starting from the root, the variable whose two polarities propagate the most literals
+    /// (by product) is chosen for branching. Literals whose negation fails the lookahead are
+    /// added to the cube directly and refuted branches produce no cube, so an empty list of cubes
+    /// means the formula is unsat. The cubes cover all models of the formula.
+    ///
+    /// Returns `None`, if termination was requested.
+    /// Panics if the solver has xor or cardinality constraints or a user propagator.
+    pub fn cubes(&mut self, max_depth: usize) -> Option<Vec<Vec<i32>>> {
+        assert!(
+            self.xor_db.is_empty() && self.cardinality_db.is_empty(),
+            "cubing only supports clauses"
+        );
+        assert!(
+            self.user_propagator.is_none(),
+            "cubing doesn't support a user propagator"
+        );
+
+        self.backtrack(0);
+        if self.trivially_unsat || !self.import_clauses() {
+            return Some(Vec::new());
+        }
+        match self.propagate() {
+            PropagationResult::Done => {}
+            PropagationResult::Interrupted => return None,
+            PropagationResult::Contradiction(_) | PropagationResult::Unsat => {
+                self.trivially_unsat = true;
+                return Some(Vec::new());
+            }
+        }
+
+        let mut cubes = Vec::new();
+        let result = self.cube(&mut Vec::new(), max_depth, &mut cubes);
+        self.backtrack(0);
+        result.ok().map(|_| cubes)
+    }
+
+    /// Solve the formula under each of the `cubes` in turn, keeping learned clauses between them.
+    /// Returns the first model. If all cubes are unsat, the formula is unsat, as long as the cubes
+    /// cover all models, e.g. because they were produced by [`Solver::cubes`].
+    pub fn conquer(&mut self, cubes: &[Vec<i32>]) -> Result {
+        for cube in cubes {
+            match self.solve_with_assumptions(cube) {
+                Result::Sat(model) => return Result::Sat(model),
+                Result::Unsat(_) => continue,
+                Result::Unknown => return Result::Unknown,
+            }
+        }
+        self.failed_assumptions.clear();
+        Result::Unsat(Proof)
+    }
+
+    /// Assign `lit` as decision and propagate it.
+    fn probe(&mut self, lit: Lit) -> Probe {
+        let assigned = self.trail.assigned_vars();
+        self.trail.assign_lit(lit, TrailReason::Decision);
+        match self.propagate() {
+            PropagationResult::Done => Probe::Propagated(self.trail.assigned_vars() - assigned - 1),
+            PropagationResult::Contradiction(_) | PropagationResult::Unsat => Probe::Conflict,
+            PropagationResult::Interrupted => Probe::Interrupted,
+        }
+    }
+
+    /// Look ahead on both polarities of `var` and undo the assignments.
+    /// Returns the number of literals propagated by each polarity, `None` for a conflict.
+    fn look_ahead(&mut self, var: Var) -> std::result::Result<[Option<usize>; 2], Interrupted> {
+        let level = self.trail.current_decision_level();
+        let mut propagated = [None; 2];
+        for (count, lit) in propagated.iter_mut().zip([Lit::from(var), -Lit::from(var)]) {
+            let probe = self.probe(lit);
+            self.backtrack(level);
+            *count = match probe {
+                Probe::Propagated(count) => Some(count),
+                Probe::Conflict => None,
+                Probe::Interrupted => return Err(Interrupted),
+            };
+        }
+        Ok(propagated)
+    }
+
+    /// Extend `cube`, whose literals are assigned and propagated, and collect the resulting cubes.
+    fn cube(
+        &mut self,
+        cube: &mut Vec<i32>,
+        depth: usize,
+        cubes: &mut Vec<Vec<i32>>,
+    ) -> std::result::Result<(), Interrupted> {
+        let (level, len) = (self.trail.current_decision_level(), cube.len());
+        let result = self.cube_node(cube, depth, cubes);
+        self.backtrack(level);
+        cube.truncate(len);
+        result
+    }
+
+    fn cube_node(
+        &mut self,
+        cube: &mut Vec<i32>,
+        depth: usize,
+        cubes: &mut Vec<Vec<i32>>,
+    ) -> std::result::Result<(), Interrupted> {
+        if self.terminate_requested() {
+            return Err(Interrupted);
+        }
+
+        // Find the best branching variable, while assigning failed literals.
+        let var = 'lookahead: loop {
+            if depth == 0 || self.all_vars_assigned() {
+                cubes.push(cube.clone());
+                return Ok(());
+            }
+
+            let mut best: Option<(u64, Var)> = None;
+            for var in 1..=self.num_vars() as i32 {
+                let var = Var::new(var);
+                if !self.trail.is_lit_unassigned(Lit::from(var)) {
+                    continue;
+                }
+
+                let implied = match self.look_ahead(var)? {
+                    [Some(pos), Some(neg)] => {
+                        let score = (pos as u64 + 1) * (neg as u64 + 1);
+                        if best.is_none_or(|(best_score, _)| score > best_score) {
+                            best = Some((score, var));
+                        }
+                        continue;
+                    }
+                    [None, None] => return Ok(()),
+                    [Some(_), None] => Lit::from(var),
+                    [None, Some(_)] => -Lit::from(var),
+                };
+
+                // The other polarity fails, so every model of this cube contains `implied`.
+                cube.push(i32::from(implied));
+                match self.probe(implied) {
+                    Probe::Propagated(_) => continue 'lookahead,
+                    Probe::Conflict => return Ok(()),
+                    Probe::Interrupted => return Err(Interrupted),
+                }
+            }
+
+            break best.expect("an unassigned variable is left").1;
+        };
+
+        let level = self.trail.current_decision_level();
+        for lit in [Lit::from(var), -Lit::from(var)] {
+            self.stats.decisions += 1;
+            cube.push(i32::from(lit));
+            let result = match self.probe(lit) {
+                Probe::Propagated(_) => self.cube(cube, depth - 1, cubes),
+                Probe::Conflict => Ok(()),
+                Probe::Interrupted => Err(Interrupted),
+            };
+            self.backtrack(level);
+            cube.pop();
+            result?;
+        }
+        Ok(())
+    }
+}
@@ -84,6 +84,17 @@ impl From<Var> for Lit {
     }
 }
 
+impl From<Lit> for i32 {
+    fn from(lit: Lit) -> Self {
+        let var = lit.var().get() as i32;
+        if lit.is_pos() {
+            var
+        } else {
+            -var
+        }
+    }
+}
+
 impl std::ops::Neg for Lit {
     type Output = Lit;
 
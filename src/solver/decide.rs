@@ -0,0 +1,313 @@
+use super::{
+    config::{DecisionHeuristic, Phase},
+    data::VarVec,
+    trail::TrailElement,
+    Lit, Solver, Var,
+};
+
+/// Activities are rescaled, once one of them exceeds this limit.
+const ACTIVITY_LIMIT: f64 = 1e100;
+
+/// Factor by which the activities of all variables decay after every conflict.
+const ACTIVITY_DECAY: f64 = 0.95;
+
+/// Random number by SplitMix64, advancing `state`.
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Binary max-heap of variables ordered by a score, which supports increasing the score of
+/// contained variables.
+#[derive(Default)]
+struct VarHeap {
+    heap: Vec<Var>,
+    /// Position of each variable in `heap`.
+    positions: VarVec<Option<usize>>,
+}
+
+impl VarHeap {
+    fn contains(&self, var: Var) -> bool {
+        self.positions[var].is_some()
+    }
+
+    fn insert(&mut self, var: Var, activity: &VarVec<f64>) {
+        self.positions.expand(var, None);
+        if self.contains(var) {
+            return;
+        }
+        self.heap.push(var);
+        self.positions[var] = Some(self.heap.len() - 1);
+        self.sift_up(self.heap.len() - 1, activity);
+    }
+
+    fn pop(&mut self, activity: &VarVec<f64>) -> Option<Var> {
+        let top = *self.heap.first()?;
+        let last = self.heap.pop().unwrap();
+        self.positions[top] = None;
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.positions[last] = Some(0);
+            self.sift_down(0, activity);
+        }
+        Some(top)
+    }
+
+    /// Restore the heap order after the activity of `var` was increased.
+    fn increased(&mut self, var: Var, activity: &VarVec<f64>) {
+        if let Some(pos) = self.positions[var] {
+            self.sift_up(pos, activity);
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions[self.heap[a]] = Some(a);
+        self.positions[self.heap[b]] = Some(b);
+    }
+
+    fn sift_up(&mut self, mut pos: usize, activity: &VarVec<f64>) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if activity[self.heap[parent]] >= activity[self.heap[pos]] {
+                break;
+            }
+            self.swap(parent, pos);
+            pos = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut pos: usize, activity: &VarVec<f64>) {
+        loop {
+            let mut largest = pos;
+            for child in [2 * pos + 1, 2 * pos + 2] {
+                if child < self.heap.len()
+                    && activity[self.heap[child]] > activity[self.heap[largest]]
+                {
+                    largest = child;
+                }
+            }
+            if largest == pos {
+                break;
+            }
+            self.swap(pos, largest);
+            pos = largest;
+        }
+    }
+}
+
+/// State of the decision heuristics, see [`Config`](super::Config).
+#[derive(Default)]
+pub(crate) struct Decider {
+    activity: VarVec<f64>,
+    /// Amount by which the activity of a variable is bumped, it grows instead of decaying all
+    /// activities.
+    increment: f64,
+    heap: VarHeap,
+    /// Time of the last bump of each variable, the queue of the move-to-front heuristic is ordered
+    /// by it.
+    stamps: VarVec<f64>,
+    next_stamp: f64,
+    queue: VarHeap,
+    saved_phases: VarVec<Option<bool>>,
+    /// Phases of the largest conflict-free trail since the last restart and its length.
+    target_phases: VarVec<Option<bool>>,
+    target_len: usize,
+    /// Phases of the largest conflict-free trail since the last rephasing and its length.
+    best_phases: VarVec<Option<bool>>,
+    best_len: usize,
+    /// State of the random number generator.
+    rng: u64,
+}
+
+impl Decider {
+    pub(crate) fn seed(&mut self, seed: u64) {
+        self.rng = seed;
+    }
+
+    pub(crate) fn next_random(&mut self) -> u64 {
+        splitmix64(&mut self.rng)
+    }
+
+    /// Add the variables up to `num_vars`, which aren't known yet.
+    pub(crate) fn grow(&mut self, num_vars: usize, perturb: bool) {
+        if self.increment == 0.0 {
+            self.increment = 1.0;
+        }
+        for var in self.activity.len() + 1..=num_vars {
+            let var = Var::new(var as i32);
+            // Tiny random activities break the ties of the initial order.
+            let activity = if perturb {
+                (self.next_random() >> 11) as f64 / (1u64 << 53) as f64 * 1e-3
+            } else {
+                0.0
+            };
+            self.activity.expand(var, 0.0);
+            self.activity[var] = activity;
+            // Initially the queue prefers variables with smaller indices.
+            self.stamps.expand(var, 0.0);
+            self.stamps[var] = -(var.get() as f64);
+            self.saved_phases.expand(var, None);
+            self.target_phases.expand(var, None);
+            self.best_phases.expand(var, None);
+            self.heap.insert(var, &self.activity);
+            self.queue.insert(var, &self.stamps);
+        }
+    }
+
+    /// Save the phase of the unassigned literal `lit` and make its variable available for decisions.
+    pub(crate) fn unassigned(&mut self, lit: Lit) {
+        let var = lit.var();
+        if var.get() as usize > self.activity.len() {
+            return;
+        }
+        self.saved_phases[var] = Some(lit.is_pos());
+        self.heap.insert(var, &self.activity);
+        self.queue.insert(var, &self.stamps);
+    }
+
+    /// Overwrite the saved phase of `var`, e.g. with the value of a local search assignment.
+    pub(crate) fn save_phase(&mut self, var: Var, positive: bool) {
+        self.saved_phases.expand(var, None);
+        self.saved_phases[var] = Some(positive);
+    }
+
+    /// Set the saved phase of every variable, `None` falls back to the default [`Phase`].
+    pub(crate) fn reset_saved_phases(&mut self, positive: Option<bool>) {
+        self.saved_phases.fill(positive);
+    }
+
+    /// Overwrite the saved phases with the best phases.
+    pub(crate) fn restore_best_phases(&mut self) {
+        for (var, &best) in self.best_phases.iter_with_var() {
+            if best.is_some() {
+                self.saved_phases[var] = best;
+            }
+        }
+    }
+
+    /// Record the phases of `trail`, which was fully propagated without a conflict, if it is the
+    /// largest one since the last restart or rephasing.
+    pub(crate) fn update_target_phases(&mut self, trail: &[TrailElement]) {
+        let phases = |phases: &mut VarVec<Option<bool>>| {
+            for elem in trail {
+                if elem.lit.var().get() as usize <= phases.len() {
+                    phases[elem.lit.var()] = Some(elem.lit.is_pos());
+                }
+            }
+        };
+        if trail.len() > self.target_len {
+            self.target_len = trail.len();
+            phases(&mut self.target_phases);
+        }
+        if trail.len() > self.best_len {
+            self.best_len = trail.len();
+            phases(&mut self.best_phases);
+        }
+    }
+
+    /// Start over with the target phases, which are kept until a larger trail replaces them.
+    pub(crate) fn reset_target(&mut self) {
+        self.target_len = 0;
+    }
+
+    /// Start over with the best phases after rephasing. The target phases are set to the new
+    /// saved phases.
+    pub(crate) fn reset_best(&mut self) {
+        self.best_len = 0;
+        self.target_len = 0;
+        self.target_phases.clone_from(&self.saved_phases);
+    }
+
+    /// Bump the variables involved in a conflict for both VSIDS and the move-to-front queue.
+    pub(crate) fn bump_conflict(&mut self, vars: &mut Vec<Var>) {
+        // Moving the variables to the front keeps their relative order.
+        vars.retain(|var| var.get() as usize <= self.activity.len());
+        vars.sort_by(|&a, &b| self.stamps[a].total_cmp(&self.stamps[b]));
+
+        for &var in vars.iter() {
+            self.activity[var] += self.increment;
+            if self.activity[var] > ACTIVITY_LIMIT {
+                for activity in &mut self.activity {
+                    *activity /= ACTIVITY_LIMIT;
+                }
+                self.increment /= ACTIVITY_LIMIT;
+            }
+            self.heap.increased(var, &self.activity);
+
+            self.stamps[var] = self.next_stamp;
+            self.next_stamp += 1.0;
+            self.queue.increased(var, &self.stamps);
+        }
+        self.increment /= ACTIVITY_DECAY;
+    }
+}
+
+impl Solver {
+    /// Pick the next decision literal according to the [`Config`](super::Config).
+    pub(crate) fn decide(&mut self) -> Option<Lit> {
+        self.decider
+            .grow(self.trail.total_vars(), self.config.seed != 0);
+
+        let var = match self.decision_heuristic() {
+            DecisionHeuristic::FirstUnassigned => self.trail.find_unassigned_variable()?,
+            DecisionHeuristic::Vsids => loop {
+                let var = self.decider.heap.pop(&self.decider.activity)?;
+                if self.trail.is_lit_unassigned(Lit::from(var)) {
+                    break var;
+                }
+            },
+            DecisionHeuristic::Vmtf => loop {
+                let var = self.decider.queue.pop(&self.decider.stamps)?;
+                if self.trail.is_lit_unassigned(Lit::from(var)) {
+                    break var;
+                }
+            },
+        };
+
+        let lit = Lit::from(var);
+        Some(if self.preferred_phase(var) { lit } else { -lit })
+    }
+
+    /// Polarity for a decision on `var`: the target phase or the saved phase if enabled,
+    /// otherwise the default [`Phase`].
+    pub(crate) fn preferred_phase(&mut self, var: Var) -> bool {
+        let target = self.decider.target_phases[var].filter(|_| self.target_phases());
+        let saved = self.decider.saved_phases[var].filter(|_| self.config.phase_saving);
+        target.or(saved).unwrap_or_else(|| match self.config.phase {
+            Phase::Positive => true,
+            Phase::Negative => false,
+            Phase::Random => self.decider.next_random() & 1 == 1,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::config::Config;
+
+    #[test]
+    fn vmtf_decides_the_most_recently_bumped_variables_first() {
+        let mut solver = Solver::with_config(Config {
+            decision: DecisionHeuristic::Vmtf,
+            ..Config::default()
+        });
+        for _ in 0..4 {
+            solver.new_var();
+        }
+        solver.decider.grow(4, false);
+        solver
+            .decider
+            .bump_conflict(&mut vec![Var::new(4), Var::new(2)]);
+
+        // Bumped variables keep their relative order in front of the others.
+        let order: Vec<u32> = std::iter::from_fn(|| solver.decide())
+            .map(|lit| lit.var().get())
+            .collect();
+        assert_eq!(order, [2, 4, 1, 3]);
+    }
+}
@@ -0,0 +1,156 @@
+use super::{Model, Result, Solver};
+
+/// How already enumerated models are excluded from the search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Blocking {
+    /// Add a clause over the projection variables for every model. The clauses stay in the solver.
+    #[default]
+    Clauses,
+    /// Branch over the projection variables with assumptions and flip the last unflipped branch
+    /// after every model, so the formula doesn't grow.
+    Decisions,
+}
+
+/// Iterator over the models of a [`Solver`], created by [`Solver::models`].
+///
+/// Every yielded model differs from the previous ones on the projection variables.
+pub struct Models<'a> {
+    solver: &'a mut Solver,
+    projection: Vec<i32>,
+    blocking: Blocking,
+    limit: Option<usize>,
+    count: usize,
+
+    /// Assumed literals of the current branch, together with whether the literal was flipped.
+    branch: Vec<(i32, bool)>,
+    /// Whether the current branch was already searched.
+    searched: bool,
+    exhausted: bool,
+    interrupted: bool,
+}
+
+impl Solver {
+    /// Enumerate the models of the formula. By default, all variables are projection variables and
+    /// models are blocked with clauses.
+    pub fn models(&mut self) -> Models<'_> {
+        let projection = (1..=self.num_vars() as i32).collect();
+        Models {
+            solver: self,
+            projection,
+            blocking: Blocking::default(),
+            limit: None,
+            count: 0,
+            branch: Vec::new(),
+            searched: false,
+            exhausted: false,
+            interrupted: false,
+        }
+    }
+}
+
+impl<'a> Models<'a> {
+    /// Only enumerate models, which differ on the variables `vars`.
+    pub fn project(mut self, vars: &[i32]) -> Self {
+        self.projection = vars.iter().map(|var| var.abs()).collect();
+        self.projection.sort_unstable();
+        self.projection.dedup();
+        self
+    }
+
+    pub fn blocking(mut self, blocking: Blocking) -> Self {
+        self.blocking = blocking;
+        self
+    }
+
+    /// Stop after `limit` models.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Whether all models were enumerated.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Whether the enumeration stopped, because the solver returned [`Result::Unknown`].
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    /// Flip the deepest literal of the branch, whose other polarity wasn't searched yet.
+    fn next_branch(&mut self) {
+        while let Some((lit, flipped)) = self.branch.pop() {
+            if !flipped {
+                self.branch.push((-lit, true));
+                self.searched = false;
+                return;
+            }
+        }
+        self.exhausted = true;
+    }
+
+    fn solve(&mut self) -> Option<Model> {
+        match self.blocking {
+            Blocking::Clauses => match self.solver.solve() {
+                Result::Sat(model) => {
+                    let blocking_clause =
+                        self.projection
+                            .iter()
+                            .map(|&var| if model.lit(var) { -var } else { var });
+                    self.solver.add_clause(blocking_clause);
+                    Some(model)
+                }
+                Result::Unsat(_) => {
+                    self.exhausted = true;
+                    None
+                }
+                Result::Unknown => {
+                    self.interrupted = true;
+                    None
+                }
+            },
+            Blocking::Decisions => loop {
+                if self.searched {
+                    self.next_branch();
+                }
+                if self.exhausted {
+                    return None;
+                }
+
+                let assumptions: Vec<i32> = self.branch.iter().map(|&(lit, _)| lit).collect();
+                self.searched = true;
+                match self.solver.solve_with_assumptions(&assumptions) {
+                    Result::Sat(model) => {
+                        // The model covers the whole subtree of the fully assigned branch.
+                        for &var in &self.projection[self.branch.len()..] {
+                            self.branch
+                                .push((if model.lit(var) { var } else { -var }, false));
+                        }
+                        return Some(model);
+                    }
+                    Result::Unsat(_) => continue,
+                    Result::Unknown => {
+                        self.searched = false;
+                        self.interrupted = true;
+                        return None;
+                    }
+                }
+            },
+        }
+    }
+}
+
+impl<'a> Iterator for Models<'a> {
+    type Item = Model;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted || self.interrupted || self.limit == Some(self.count) {
+            return None;
+        }
+
+        let model = self.solve()?;
+        self.count += 1;
+        Some(model)
+    }
+}
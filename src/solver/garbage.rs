@@ -72,7 +72,7 @@ impl Solver {
     /// Checks if garbage collection limit is reached, and if so, will collect garbage clauses.
     pub(crate) fn maybe_collect_garbage(&mut self) {
         if self.stats.contradiction_since_last_garbage_collections
-            >= self.limits.garbage_collection_conflicts
+            < self.limits.garbage_collection_conflicts
         {
             return;
         }
@@ -86,3 +86,21 @@ impl Solver {
         self.collect_garbage();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn garbage_is_collected_at_the_conflict_limit() {
+        let mut solver = Solver::new();
+        solver.stats.contradiction_since_last_garbage_collections = 1;
+        solver.maybe_collect_garbage();
+        assert_eq!(solver.stats.contradiction_since_last_garbage_collections, 1);
+
+        solver.stats.contradiction_since_last_garbage_collections =
+            solver.limits.garbage_collection_conflicts;
+        solver.maybe_collect_garbage();
+        assert_eq!(solver.stats.contradiction_since_last_garbage_collections, 0);
+    }
+}
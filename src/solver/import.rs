@@ -0,0 +1,249 @@
+use std::{
+    num::NonZeroU32,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+use tracing::debug;
+
+use super::{clause::ClauseIdx, trail::TrailReason, watch::Watch, Lit, Solver};
+
+/// Handle to inject clauses into a solver, e.g. clauses learned by another solver instance.
+///
+/// The clauses have to be implied by the formula of the receiving solver, because they are treated as
+/// redundant (learned) clauses.
+/// They are integrated into the search the next time the solver reaches a safe point, i.e. at the start of
+/// [`Solver::solve`] and after backtracking from a conflict.
+#[derive(Clone)]
+pub struct ClauseImporter(Sender<(Vec<i32>, u32)>);
+
+impl ClauseImporter {
+    /// Queue `clause` with the given glue (LBD) value for import.
+    /// Returns false, if the receiving solver doesn't exist anymore.
+    pub fn import(&self, clause: Vec<i32>, glue: u32) -> bool {
+        self.0.send((clause, glue)).is_ok()
+    }
+}
+
+pub(crate) struct ImportQueue {
+    sender: Sender<(Vec<i32>, u32)>,
+    receiver: Receiver<(Vec<i32>, u32)>,
+}
+
+impl Default for ImportQueue {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self { sender, receiver }
+    }
+}
+
+impl Solver {
+    /// Get a handle which can be used to inject clauses into this solver.
+    pub fn clause_importer(&self) -> ClauseImporter {
+        ClauseImporter(self.import_queue.sender.clone())
+    }
+
+    /// Integrate all queued clauses into the clause database.
+    /// Returns false if an imported clause shows that the formula is unsat.
+    pub(crate) fn import_clauses(&mut self) -> bool {
+        while let Ok((clause, glue)) = self.import_queue.receiver.try_recv() {
+            debug!("importing clause {clause:?}");
+            let clause = clause.into_iter().map(Lit::new).collect();
+            let glue = NonZeroU32::new(glue).unwrap_or(NonZeroU32::MIN);
+
+            if !self.integrate_clause(clause, Some(glue)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Add a clause to the clause database, taking the current assignment of the trail into account.
+    /// If the clause is unit under the current assignment, its literal is propagated
+    /// and if it is falsified we backtrack until it isn't anymore.
+    /// Clauses with a glue value are considered redundant.
+    ///
+    /// Returns false if the clause is falsified on decision level zero, i.e. the formula is unsat.
+    pub(crate) fn integrate_clause(&mut self, mut cls: Vec<Lit>, glue: Option<NonZeroU32>) -> bool {
+        if Self::normalise_clause(&mut cls) {
+            return true;
+        };
+
+        let max_lit = cls.iter().max_by_key(|l| l.var().get());
+
+        if let Some(max_lit) = max_lit {
+            self.trail.expand(max_lit.var());
+            self.watches.expand(-Lit::from(max_lit.var()), Vec::new())
+        }
+
+        // Assignments of decision level zero are permanent, so we can simplify the clause with them.
+        let is_root_level = |lit: Lit| self.trail.get_decision_level(lit) == Some(0);
+        if cls
+            .iter()
+            .any(|&lit| is_root_level(lit) && self.trail.is_lit_satisfied(lit))
+        {
+            return true;
+        }
+        cls.retain(|&lit| !(is_root_level(lit) && self.trail.is_lit_unsatisfied(lit)));
+
+        match cls.len() {
+            0 => {
+                self.trivially_unsat = true;
+                return false;
+            }
+            1 => {
+                self.backtrack(0);
+                self.trail.assign_lit(cls[0], TrailReason::Axiom);
+                return true;
+            }
+            _ => {}
+        }
+
+        self.order_for_watching(&mut cls);
+
+        let level = |lit: Lit| self.trail.get_decision_level(lit);
+        let mut propagate = false;
+
+        match (
+            self.trail.get_lit_assignment(cls[0]),
+            self.trail.get_lit_assignment(cls[1]),
+        ) {
+            // Two non falsified literals can be watched.
+            (_, Some(true) | None) => {}
+
+            // The satisfied literal was assigned before all unsatisfied literals,
+            // so it stays satisfied as long as the clause isn't unit.
+            (Some(true), Some(false)) if level(cls[0]) <= level(cls[1]) => {}
+
+            // The clause is unit, once we backtrack to the highest level of the unsatisfied literals.
+            (Some(true) | None, Some(false)) => {
+                self.backtrack(level(cls[1]).unwrap());
+                propagate = true;
+            }
+
+            // The clause is falsified.
+            (Some(false), Some(false)) => {
+                let (level0, level1) = (level(cls[0]).unwrap(), level(cls[1]).unwrap());
+                if level0 > level1 {
+                    // Only a single literal on the highest level, after backtracking the clause is unit.
+                    self.backtrack(level1);
+                    propagate = true;
+                } else {
+                    // After backtracking both watched literals are unassigned.
+                    self.backtrack(level0 - 1);
+                }
+            }
+        }
+
+        let cls_idx = self.attach_clause(&cls, glue);
+
+        if propagate {
+            debug_assert!(self.trail.is_lit_unassigned(cls[0]));
+            debug!("imported clause is unit, assigning {}", cls[0]);
+            self.trail
+                .assign_lit(cls[0], TrailReason::Propagated { cls: cls_idx });
+            self.clause_db.get_mut(cls_idx).flags().set_is_reason(true);
+            self.stats.propagations += 1;
+        }
+
+        true
+    }
+
+    /// Order the literals of a clause, such that the first two are the best candidates to be watched:
+    /// satisfied literals with low decision levels first, then unassigned literals and
+    /// at last unsatisfied literals with high decision levels.
+    pub(crate) fn order_for_watching(&self, cls: &mut [Lit]) {
+        cls.sort_by_key(|&lit| match self.trail.get_lit_assignment(lit) {
+            Some(true) => (0, self.trail.get_decision_level(lit).unwrap()),
+            None => (1, 0),
+            Some(false) => (2, u32::MAX - self.trail.get_decision_level(lit).unwrap()),
+        });
+    }
+
+    /// Insert the clause into the clause database and watch its first two literals.
+    pub(crate) fn attach_clause(&mut self, cls: &[Lit], glue: Option<NonZeroU32>) -> ClauseIdx {
+        debug_assert!(cls.len() >= 2);
+
+        let cls_idx = self.clause_db.insert_clause(cls, glue);
+        for &lit in &cls[0..2] {
+            self.watches[lit].push(Watch { clause: cls_idx });
+        }
+        cls_idx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Solver with the variables 1..=6 and the given decisions, one per decision level.
+    fn solver_with_decisions(decisions: &[i32]) -> Solver {
+        let mut solver = Solver::new();
+        solver.add_clause([1, 2, 3, 4, 5, 6]);
+        for &lit in decisions {
+            solver
+                .trail
+                .assign_lit(Lit::new(lit), TrailReason::Decision);
+        }
+        solver.unpropagated_lit_pos = solver.trail.assigned_vars();
+        solver
+    }
+
+    fn integrate(solver: &mut Solver, clause: &[i32]) -> bool {
+        let clause = clause.iter().copied().map(Lit::new).collect();
+        solver.integrate_clause(clause, NonZeroU32::new(2))
+    }
+
+    #[test]
+    fn unit_clause_is_propagated() {
+        let mut solver = solver_with_decisions(&[1, 2, 3]);
+        assert!(integrate(&mut solver, &[-1, -2, 4]));
+
+        // Backtrack to the level of `-2`, where the clause became unit.
+        assert_eq!(solver.trail.current_decision_level(), 2);
+        assert!(solver.trail.is_lit_satisfied(Lit::new(4)));
+        assert_eq!(solver.trail.get_decision_level(Lit::new(4)), Some(2));
+    }
+
+    #[test]
+    fn falsified_clause_backtracks() {
+        let mut solver = solver_with_decisions(&[1, 2, 3]);
+        assert!(integrate(&mut solver, &[-1, -3]));
+        assert_eq!(solver.trail.current_decision_level(), 1);
+        assert!(solver.trail.is_lit_satisfied(Lit::new(-3)));
+
+        // Two literals on the highest level.
+        let mut solver = solver_with_decisions(&[1, 2, 3]);
+        solver.trail.assign_lit(Lit::new(4), TrailReason::Axiom);
+        assert!(integrate(&mut solver, &[-3, -4, -1]));
+        assert_eq!(solver.trail.current_decision_level(), 2);
+        assert!(solver.trail.is_lit_unassigned(Lit::new(3)));
+        assert!(solver.trail.is_lit_unassigned(Lit::new(4)));
+    }
+
+    #[test]
+    fn satisfied_clause_stays_assigned() {
+        let mut solver = solver_with_decisions(&[1, 2, 3]);
+        assert!(integrate(&mut solver, &[2, -3]));
+        assert_eq!(solver.trail.current_decision_level(), 3);
+
+        // The satisfied literal is assigned after the unsatisfied one, so it has to be propagated.
+        let mut solver = solver_with_decisions(&[1, 2, 3]);
+        assert!(integrate(&mut solver, &[3, -1]));
+        assert_eq!(solver.trail.current_decision_level(), 1);
+        assert!(solver.trail.is_lit_satisfied(Lit::new(3)));
+    }
+
+    #[test]
+    fn root_level() {
+        let mut solver = solver_with_decisions(&[]);
+        solver.trail.assign_lit(Lit::new(1), TrailReason::Axiom);
+        solver.trail.assign_lit(Lit::new(2), TrailReason::Decision);
+
+        assert!(integrate(&mut solver, &[-1, 3]));
+        assert_eq!(solver.trail.current_decision_level(), 0);
+        assert!(solver.trail.is_lit_satisfied(Lit::new(3)));
+
+        assert!(!integrate(&mut solver, &[-1, -3]));
+        assert!(solver.trivially_unsat);
+    }
+}
@@ -0,0 +1,49 @@
+use super::{Lit, Solver};
+
+type LearnFn = Box<dyn FnMut(&[i32], u32) + Send>;
+
+/// User callback for exported learned clauses, together with the filter of which clauses to export.
+pub(crate) struct LearnCallback {
+    max_len: usize,
+    max_glue: u32,
+    callback: LearnFn,
+
+    /// Buffer for the literals of the exported clause, so we don't allocate for every clause.
+    buffer: Vec<i32>,
+}
+
+impl LearnCallback {
+    /// Pass `clause` to the user callback, if it is short enough and has a small enough glue value.
+    pub(crate) fn export(&mut self, clause: &[Lit], glue: u32) {
+        if clause.len() > self.max_len || glue > self.max_glue {
+            return;
+        }
+
+        self.buffer.clear();
+        self.buffer.extend(clause.iter().map(|&lit| i32::from(lit)));
+        (self.callback)(&self.buffer, glue);
+    }
+}
+
+impl Solver {
+    /// Set a callback which is invoked with every learned clause with at most `max_len` literals
+    /// and a glue (LBD) value of at most `max_glue`.
+    /// Learned unit clauses are exported with a glue value of one.
+    pub fn set_learn(
+        &mut self,
+        max_len: usize,
+        max_glue: u32,
+        callback: impl FnMut(&[i32], u32) + Send + 'static,
+    ) {
+        self.learn_callback = Some(LearnCallback {
+            max_len,
+            max_glue,
+            callback: Box::new(callback),
+            buffer: Vec::new(),
+        });
+    }
+
+    pub fn clear_learn(&mut self) {
+        self.learn_callback = None;
+    }
+}
@@ -32,6 +32,9 @@ impl Solver {
                 TrailReason::Decision => "D",
                 TrailReason::Propagated { .. } => "P",
                 TrailReason::Axiom => "A",
+                TrailReason::External => "E",
+                TrailReason::Xor => "X",
+                TrailReason::Cardinality => "C",
             };
 
             writeln!(
@@ -92,6 +95,9 @@ impl Trail {
                     TrailReason::Decision => format!("{lit}D"),
                     TrailReason::Propagated { .. } => format!("{lit}P"),
                     TrailReason::Axiom => format!("{lit}A"),
+                    TrailReason::External => format!("{lit}E"),
+                    TrailReason::Xor => format!("{lit}X"),
+                    TrailReason::Cardinality => format!("{lit}C"),
                 }
             })
             .intersperse(", ".to_string());
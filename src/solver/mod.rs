@@ -1,30 +1,84 @@
 mod analyze;
 mod assignment;
+mod backbone;
+mod cardinality;
 mod clause;
+mod config;
+mod count;
+mod cube;
 mod data;
+mod decide;
+mod enumerate;
 mod garbage;
+mod import;
+mod learn;
 mod log;
+mod mode;
+mod model;
 mod propagate;
+mod propagator;
+mod rephase;
+mod restart;
+mod terminate;
 mod trail;
+mod walk;
 mod watch;
+mod xor;
 
 use analyze::AnalyzeResult;
+pub use backbone::BackboneResult;
 
+use crate::dimacs::Constraint;
+use cardinality::CardinalityDB;
 use clause::ClauseDB;
+pub use config::{Config, DecisionHeuristic, Phase, Restarts};
 use data::LitVec;
 pub use data::{Lit, Var};
+use decide::Decider;
+pub use enumerate::{Blocking, Models};
+pub use import::ClauseImporter;
+use import::ImportQueue;
+use learn::LearnCallback;
+use mode::ModeState;
+pub use mode::{ModeStats, SearchMode};
+pub use model::Model;
 use propagate::PropagationResult;
+pub use propagator::UserPropagator;
+use propagator::{ModelCheck, PropagatorState};
+use rephase::RephaseState;
+use restart::RestartState;
+use std::time::Instant;
+use terminate::TerminateCallback;
+pub use terminate::TerminateHandle;
 use tracing::debug;
 use trail::{Trail, TrailReason};
 use watch::Watch;
+use xor::XorDB;
 
 use self::analyze::AnalyzeState;
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Stats {
     pub contradictions: u64,
     pub propagations: u64,
+    pub decisions: u64,
     pub contradiction_since_last_garbage_collections: u64,
+    pub restarts: u64,
+    /// Backbone candidates, which were tested with a call to the solver.
+    pub backbone_checks: u64,
+    /// Backbone candidates, which were dropped, because a model falsified them.
+    pub backbone_filtered: u64,
+    /// Literals, which were found to be part of the backbone.
+    pub backbone_literals: u64,
+    /// Replacements of the saved phases, see [`Config::rephase_interval`].
+    pub rephases: u64,
+    /// Search in focused and stable mode, see [`Config::mode_switch_interval`].
+    pub focused: ModeStats,
+    pub stable: ModeStats,
+    pub mode_switches: u64,
+    /// Local search runs and the variables they flipped.
+    pub walks: u64,
+    pub walk_flips: u64,
 }
 
 pub struct Limits {
@@ -40,10 +94,27 @@ impl Default for Limits {
     }
 }
 
+/// Resource limits for a single call to [`Solver::solve`].
+/// The counting limits are relative to the start of each call, the deadline is absolute.
+/// If any of them is exceeded, the solver gives up and returns [`Result::Unknown`].
+#[derive(Default, Debug, Clone)]
+pub struct Budget {
+    pub max_conflicts: Option<u64>,
+    pub max_propagations: Option<u64>,
+    pub max_decisions: Option<u64>,
+    pub deadline: Option<Instant>,
+}
+
 #[derive(Default)]
 pub struct Solver {
     clause_db: ClauseDB,
 
+    /// Xor constraints, which are handled by Gauss-Jordan elimination.
+    xor_db: XorDB,
+
+    /// At-most-k constraints, which are handled by counting satisfied literals.
+    cardinality_db: CardinalityDB,
+
     watches: LitVec<Vec<Watch>>,
 
     trail: Trail,
@@ -51,8 +122,12 @@ pub struct Solver {
     // Where on the trail, should the unit propgation continue.
     unpropagated_lit_pos: usize,
 
+    // Literals propagated since termination was last polled during propagation.
+    propagations_since_poll: usize,
+
     // The input cnf formula is trivially unsat.
     // This might be because an empty clause was added or contradictory unit clauses.
+    // Once a contradiction is derived on decision level zero, this is set as well.
     trivially_unsat: bool,
 
     /// Various stats that might be of interest
@@ -64,48 +139,57 @@ pub struct Solver {
 
     /// Certain (dynamic limits) that are used to determine Behaviour
     limits: Limits,
-}
 
-pub struct Model<'a> {
-    assignment: &'a Trail,
-}
+    /// User supplied resource limits for calls to `solve`.
+    budget: Budget,
 
-impl<'a> Model<'a> {
-    /// Get truth assignment of literal.
-    pub fn lit(&self, l: i32) -> bool {
-        self.assignment.is_lit_satisfied(Lit::new(l))
-    }
+    /// Search heuristics.
+    config: Config,
 
-    pub fn as_vec(&self) -> Vec<i32> {
-        (1..=self.assignment.total_vars())
-            .map(|i| {
-                let i = i as i32;
-                i * if self.assignment.is_lit_satisfied(Lit::new(i)) {
-                    1
-                } else {
-                    -1
-                }
-            })
-            .collect()
-    }
-}
+    /// Activities and saved phases for the decision heuristic.
+    decider: Decider,
 
-impl std::fmt::Debug for Model<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_tuple("Model").field(&self.as_vec()).finish()
-    }
+    restart_state: RestartState,
+
+    rephase_state: RephaseState,
+
+    mode_state: ModeState,
+
+    /// Shared flag to stop the search from the outside.
+    terminate_handle: TerminateHandle,
+
+    /// Optional user callback, which is polled to decide if the search should be stopped.
+    terminate_callback: Option<TerminateCallback>,
+
+    /// Assumptions of the current `solve` call. They are always decided before any other variable.
+    assumptions: Vec<Lit>,
+
+    /// Subset of the assumptions, which were responsible for the last unsat result.
+    failed_assumptions: Vec<i32>,
+
+    /// Optional user callback, which receives learned clauses.
+    learn_callback: Option<LearnCallback>,
+
+    /// Clauses learned elsewhere, which are waiting to be added to the clause database.
+    import_queue: ImportQueue,
+
+    /// External propagator for domain specific constraints.
+    user_propagator: Option<PropagatorState>,
 }
 
 #[derive(Debug)]
 pub struct Proof;
 
 #[derive(Debug)]
-pub enum Result<'a> {
-    Sat(Model<'a>),
+pub enum Result {
+    Sat(Model),
     Unsat(Proof),
+    /// The solver gave up, because the [`Budget`] was exhausted, termination was requested
+    /// or the [`UserPropagator`] rejected a model without providing a falsified clause.
+    Unknown,
 }
 
-impl<'a> Result<'a> {
+impl Result {
     pub fn is_sat(&self) -> bool {
         matches!(self, Result::Sat(_))
     }
@@ -114,10 +198,14 @@ impl<'a> Result<'a> {
         matches!(self, Result::Unsat(_))
     }
 
-    pub fn unwrap_sat(self) -> Model<'a> {
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Result::Unknown)
+    }
+
+    pub fn unwrap_sat(self) -> Model {
         match self {
             Result::Sat(model) => model,
-            Result::Unsat(_) => panic!("Result is not SAT."),
+            Result::Unsat(_) | Result::Unknown => panic!("Result is not SAT."),
         }
     }
 
@@ -125,6 +213,7 @@ impl<'a> Result<'a> {
         match self {
             Result::Unsat(proof) => proof,
             Result::Sat(_) => panic!("Result is SAT."),
+            Result::Unknown => panic!("Result is UNKNOWN."),
         }
     }
 }
@@ -135,11 +224,14 @@ impl Solver {
     }
 
     pub fn from_dimacs(input: &str) -> std::result::Result<Solver, Box<dyn std::error::Error>> {
-        let clauses = crate::dimacs::Dimacs::parse(input)?;
+        let constraints = crate::dimacs::Dimacs::parse_constraints(input)?;
 
         let mut solver = Solver::new();
-        for clause in clauses {
-            solver.add_clause(clause);
+        for constraint in constraints {
+            match constraint {
+                Constraint::Clause(clause) => solver.add_clause(clause),
+                Constraint::Xor(xor) => solver.add_xor(xor),
+            }
         }
 
         Ok(solver)
@@ -158,37 +250,24 @@ impl Solver {
     where
         I: IntoIterator<Item = i32>,
     {
-        let mut cls: Vec<Lit> = cls.into_iter().map(Lit::new).collect();
+        let cls: Vec<Lit> = cls.into_iter().map(Lit::new).collect();
 
-        if Self::normalise_clause(&mut cls) {
-            return;
-        };
-
-        let max_lit = cls.iter().max_by_key(|l| l.var().get());
+        // A previous call to `solve` might have left assignments on the trail.
+        self.backtrack(0);
+        self.integrate_clause(cls, None);
+    }
 
-        if let Some(max_lit) = max_lit {
-            self.trail.expand(max_lit.var());
-            self.watches.expand(-Lit::from(max_lit.var()), Vec::new())
-        }
+    /// Number of variables known to the solver, i.e. the largest variable used so far.
+    pub fn num_vars(&self) -> usize {
+        self.trail.total_vars()
+    }
 
-        match cls.len() {
-            0 => {
-                self.trivially_unsat = true;
-            }
-            1 => {
-                if self.trail.is_lit_unsatisfied(cls[0]) {
-                    self.trivially_unsat = true;
-                } else {
-                    self.trail.assign_lit(cls[0], TrailReason::Axiom);
-                }
-            }
-            _ => {
-                let cls_idx = self.clause_db.insert_clause(&cls, None);
-                for &lit in &cls[0..2] {
-                    self.watches[lit].push(Watch { clause: cls_idx });
-                }
-            }
-        }
+    /// Allocate a fresh variable, which doesn't occur in any constraint yet.
+    pub fn new_var(&mut self) -> i32 {
+        let var = Var::new(self.num_vars() as i32 + 1);
+        self.trail.expand(var);
+        self.watches.expand(-Lit::from(var), Vec::new());
+        var.get() as i32
     }
 
     fn all_vars_assigned(&self) -> bool {
@@ -196,34 +275,142 @@ impl Solver {
     }
 
     fn extract_model(&self) -> Model {
-        Model {
-            assignment: &self.trail,
+        Model::from_trail(&self.trail)
+    }
+
+    /// Backtrack to decision level `lvl`, resetting the reason flags of all removed propagations.
+    pub(crate) fn backtrack(&mut self, lvl: u32) {
+        if self.trail.current_decision_level() <= lvl {
+            return;
         }
+
+        let clause_db = &mut self.clause_db;
+        let cardinality_db = &mut self.cardinality_db;
+        let decider = &mut self.decider;
+        self.unpropagated_lit_pos = self.trail.backtrack(lvl, |trail_elem| {
+            if let TrailReason::Propagated { cls } = trail_elem.reason {
+                clause_db.get_mut(cls).flags().set_is_reason(false);
+            }
+            cardinality_db.unassign(trail_elem.lit);
+            decider.unassigned(trail_elem.lit);
+        });
+        self.cardinality_db.backtrack(self.unpropagated_lit_pos);
+
+        self.xor_db.backtrack(self.trail.assigned_vars());
+        self.propagator_backtrack(lvl);
     }
 
-    fn decide(&mut self) -> Option<Var> {
-        self.trail.find_unassigned_variable()
+    /// Find the next assumption which has to be decided.
+    /// Returns `Err` with the assumption, if it is already falsified by the current trail.
+    fn next_assumption(&self) -> Option<std::result::Result<Lit, Lit>> {
+        self.assumptions
+            .iter()
+            .copied()
+            .filter(|&lit| !self.trail.is_lit_satisfied(lit))
+            .map(|lit| {
+                if self.trail.is_lit_unassigned(lit) {
+                    Ok(lit)
+                } else {
+                    Err(lit)
+                }
+            })
+            .next()
     }
 
     pub fn solve(&mut self) -> Result {
+        self.solve_with_assumptions(&[])
+    }
+
+    /// Solve the formula under the assumption that all literals in `assumptions` are true.
+    /// The assumptions only hold for this call.
+    /// If the result is unsat, [`Solver::failed_assumptions`] contains the assumptions used to
+    /// derive the contradiction.
+    pub fn solve_with_assumptions(&mut self, assumptions: &[i32]) -> Result {
+        self.failed_assumptions.clear();
+
         if self.trivially_unsat {
             return Result::Unsat(Proof);
         }
 
+        // Start from scratch, if the previous call left a (partial) assignment.
+        self.backtrack(0);
+
+        self.assumptions.clear();
+        for &lit in assumptions {
+            let lit = Lit::new(lit);
+            self.trail.expand(lit.var());
+            self.watches.expand(-Lit::from(lit.var()), Vec::new());
+            self.assumptions.push(lit);
+        }
+
+        if !self.import_clauses() {
+            return Result::Unsat(Proof);
+        }
+
+        let stats_at_start = self.stats.clone();
+
         loop {
+            if self.budget_exhausted(&stats_at_start) || self.terminate_requested() {
+                debug!("Giving up.");
+                self.backtrack(0);
+                return Result::Unknown;
+            }
+
             self.log_state();
             let result = self.propagate();
 
-            if let PropagationResult::Contradiction(conflicting_clause) = result {
+            if result == PropagationResult::Interrupted {
+                self.backtrack(0);
+                return Result::Unknown;
+            } else if result == PropagationResult::Unsat {
+                debug!("Problem is Unsat because of external clause");
+                return Result::Unsat(Proof);
+            } else if let PropagationResult::Contradiction(conflicting_clause) = result {
                 if self.analyze_contradiction(conflicting_clause) == AnalyzeResult::Unsat {
                     debug!("Problem is Unsat");
+                    // The contradiction was derived without any decisions,
+                    // so adding more clauses can't make the formula satisfiable again.
+                    self.trivially_unsat = true;
                     return Result::Unsat(Proof);
-                } else {
-                    // 'analyze_contradiction` has flipped a decision variable.
-                    // We need to start at the beginning with unit propagation.
+                }
+
+                self.maybe_restart();
+                self.maybe_rephase();
+                self.maybe_switch_mode();
+                if !self.import_clauses() {
+                    debug!("Problem is Unsat because of imported clause");
+                    return Result::Unsat(Proof);
+                }
+
+                // 'analyze_contradiction` has flipped a decision variable.
+                // We need to start at the beginning with unit propagation.
+                continue;
+            }
+
+            match self.next_assumption() {
+                Some(Ok(lit)) => {
+                    debug!("deciding assumption {lit}");
+                    self.count_decision();
+                    self.trail.assign_lit(lit, TrailReason::Decision);
                     continue;
                 }
-            } else if self.all_vars_assigned() {
+                Some(Err(lit)) => {
+                    debug!("assumption {lit} is falsified, problem is Unsat under assumptions");
+                    self.analyze_final(lit);
+                    self.backtrack(0);
+                    return Result::Unsat(Proof);
+                }
+                None => {}
+            }
+
+            if self.all_vars_assigned() {
+                match self.user_check_model() {
+                    ModelCheck::Accepted => {}
+                    ModelCheck::Rejected if self.trivially_unsat => return Result::Unsat(Proof),
+                    ModelCheck::Rejected => continue,
+                    ModelCheck::ContractViolated => return Result::Unknown,
+                }
+
                 // When all variables are assigned we have to have a satisfying assignment, otherwise the
                 // propagation result would have been `Contradiction`
                 let model = self.extract_model();
@@ -237,10 +424,18 @@ impl Solver {
 
             self.maybe_collect_garbage();
 
+            if let Some(lit) = self.user_decide() {
+                debug!("new decision literal {lit} from user propagator");
+                self.count_decision();
+                self.trail.assign_lit(lit, TrailReason::Decision);
+                continue;
+            }
+
             match self.decide() {
-                Some(var) => {
-                    debug!("new decision variable {var}");
-                    self.trail.assign_lit(var.into(), TrailReason::Decision)
+                Some(lit) => {
+                    debug!("new decision literal {lit}");
+                    self.count_decision();
+                    self.trail.assign_lit(lit, TrailReason::Decision)
                 }
                 None => {
                     unreachable!("
@@ -253,11 +448,52 @@ impl Solver {
         }
     }
 
+    /// The assumptions of the last `solve_with_assumptions` call, which were used to show that the
+    /// formula is unsat. Empty, if the formula is unsat without assumptions.
+    pub fn failed_assumptions(&self) -> &[i32] {
+        &self.failed_assumptions
+    }
+
+    /// Check if the assumption `lit` is part of the failed assumptions of the last unsat result.
+    pub fn failed(&self, lit: i32) -> bool {
+        self.failed_assumptions.contains(&lit)
+    }
+
+    /// Limit the resources of subsequent calls to `solve`.
+    pub fn set_budget(&mut self, budget: Budget) {
+        self.budget = budget;
+    }
+
+    fn budget_exhausted(&self, stats_at_start: &Stats) -> bool {
+        let exceeds = |limit: Option<u64>, now: u64, start: u64| {
+            limit.is_some_and(|limit| now - start >= limit)
+        };
+
+        exceeds(
+            self.budget.max_conflicts,
+            self.stats.contradictions,
+            stats_at_start.contradictions,
+        ) || exceeds(
+            self.budget.max_propagations,
+            self.stats.propagations,
+            stats_at_start.propagations,
+        ) || exceeds(
+            self.budget.max_decisions,
+            self.stats.decisions,
+            stats_at_start.decisions,
+        ) || self
+            .budget
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
     /// check if the current assignment, satisfies the entire input formula.
     fn check_assignment(&self) -> bool {
         self.clause_db
             .iter()
             .all(|clause| self.trail.is_clause_satisfied(clause))
+            && self.xor_db.is_satisfied(&self.trail)
+            && self.cardinality_db.is_satisfied(&self.trail)
     }
 
     pub fn stats(&self) -> &Stats {
@@ -0,0 +1,123 @@
+use tracing::debug;
+
+use super::{
+    config::{DecisionHeuristic, Restarts},
+    Solver,
+};
+
+/// Restart unit of the Luby sequence in stable mode.
+const STABLE_LUBY_UNIT: u64 = 512;
+
+/// Search modes, which alternate if [`Config::mode_switch_interval`](super::Config::mode_switch_interval)
+/// is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// Frequent Glucose restarts and the move-to-front decision heuristic, which quickly refute
+    /// unsatisfiable parts of the search space.
+    #[default]
+    Focused,
+    /// VSIDS with target phases and Luby restarts, which dives deep towards models.
+    Stable,
+}
+
+/// Counters of the search in one mode.
+#[derive(Default, Debug, Clone)]
+pub struct ModeStats {
+    pub conflicts: u64,
+    pub decisions: u64,
+    pub restarts: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct ModeState {
+    mode: SearchMode,
+    /// Conflicts since the last mode switch.
+    conflicts: u64,
+    /// Number of conflicts, after which the current mode ends.
+    length: u64,
+}
+
+impl Solver {
+    /// The current search mode, or `None` if modes don't alternate.
+    pub fn search_mode(&self) -> Option<SearchMode> {
+        self.config
+            .mode_switch_interval
+            .map(|_| self.mode_state.mode)
+    }
+
+    pub(crate) fn decision_heuristic(&self) -> DecisionHeuristic {
+        match self.search_mode() {
+            None => self.config.decision,
+            Some(SearchMode::Focused) => DecisionHeuristic::Vmtf,
+            Some(SearchMode::Stable) => DecisionHeuristic::Vsids,
+        }
+    }
+
+    pub(crate) fn restart_policy(&self) -> Restarts {
+        match self.search_mode() {
+            None => self.config.restarts,
+            Some(SearchMode::Focused) => Restarts::Glucose,
+            Some(SearchMode::Stable) => Restarts::Luby {
+                unit: STABLE_LUBY_UNIT,
+            },
+        }
+    }
+
+    pub(crate) fn target_phases(&self) -> bool {
+        match self.search_mode() {
+            None => self.config.target_phases,
+            Some(mode) => mode == SearchMode::Stable,
+        }
+    }
+
+    /// Counters of the current search mode, if modes alternate.
+    pub(crate) fn mode_stats(&mut self) -> Option<&mut ModeStats> {
+        match self.search_mode()? {
+            SearchMode::Focused => Some(&mut self.stats.focused),
+            SearchMode::Stable => Some(&mut self.stats.stable),
+        }
+    }
+
+    pub(crate) fn count_decision(&mut self) {
+        self.stats.decisions += 1;
+        if let Some(stats) = self.mode_stats() {
+            stats.decisions += 1;
+        }
+    }
+
+    /// Count a conflict and switch to the other search mode, once the current one has lasted long
+    /// enough. Switching backtracks to decision level zero.
+    pub(crate) fn maybe_switch_mode(&mut self) {
+        let Some(interval) = self.config.mode_switch_interval else {
+            return;
+        };
+        if let Some(stats) = self.mode_stats() {
+            stats.conflicts += 1;
+        }
+
+        let state = &mut self.mode_state;
+        if state.length == 0 {
+            state.length = interval;
+        }
+        state.conflicts += 1;
+        if state.conflicts < state.length {
+            return;
+        }
+
+        state.mode = match state.mode {
+            SearchMode::Focused => SearchMode::Stable,
+            SearchMode::Stable => SearchMode::Focused,
+        };
+        debug!(
+            "switching to {:?} mode after {} conflicts",
+            state.mode, state.conflicts
+        );
+        state.conflicts = 0;
+        state.length = state.length.saturating_mul(2);
+        self.stats.mode_switches += 1;
+
+        self.backtrack(0);
+        self.restart_state.reset();
+        self.decider.reset_target();
+    }
+}
@@ -0,0 +1,178 @@
+use super::{data::VarVec, trail::Trail, Lit, Var};
+
+/// A satisfying assignment, detached from the solver that produced it.
+///
+/// The values are stored as a compact bit vector, one bit per variable.
+/// Because the model doesn't borrow the [`Solver`](super::Solver), it can be kept around while
+/// clauses are added and the solver is invoked again.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Model {
+    values: Vec<u64>,
+    num_vars: usize,
+}
+
+impl Model {
+    /// Capture the current (complete) assignment of the trail.
+    pub(crate) fn from_trail(trail: &Trail) -> Self {
+        let num_vars = trail.total_vars();
+        let mut values = vec![0; num_vars.div_ceil(64)];
+
+        for var in 1..=num_vars {
+            let lit = Lit::from(Var::new(var as i32));
+            debug_assert!(!trail.is_lit_unassigned(lit));
+            if trail.is_lit_satisfied(lit) {
+                let idx = var - 1;
+                values[idx / 64] |= 1 << (idx % 64);
+            }
+        }
+
+        Self { values, num_vars }
+    }
+
+    /// Capture a complete assignment, which wasn't produced on the trail.
+    pub(crate) fn from_values(values: &VarVec<bool>) -> Self {
+        let num_vars = values.len();
+        let mut bits = vec![0; num_vars.div_ceil(64)];
+
+        for (var, &value) in values.iter_with_var() {
+            if value {
+                let idx = var.get() as usize - 1;
+                bits[idx / 64] |= 1 << (idx % 64);
+            }
+        }
+
+        Self {
+            values: bits,
+            num_vars,
+        }
+    }
+
+    /// Drop the variables after `num_vars`, e.g. auxiliary variables of an encoding.
+    pub(crate) fn truncate(&mut self, num_vars: usize) {
+        if num_vars >= self.num_vars {
+            return;
+        }
+
+        self.values.truncate(num_vars.div_ceil(64));
+        if !num_vars.is_multiple_of(64) {
+            if let Some(last) = self.values.last_mut() {
+                *last &= (1 << (num_vars % 64)) - 1;
+            }
+        }
+        self.num_vars = num_vars;
+    }
+
+    /// Number of variables in this model.
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// Get the truth value of variable `var`.
+    /// Returns `None` if the variable is not part of the model.
+    pub fn value(&self, var: i32) -> Option<bool> {
+        if var <= 0 || var as usize > self.num_vars {
+            return None;
+        }
+
+        let idx = var as usize - 1;
+        Some(self.values[idx / 64] & (1 << (idx % 64)) != 0)
+    }
+
+    /// Get truth assignment of literal.
+    /// Literals over variables which are not part of the model are never satisfied.
+    pub fn lit(&self, l: i32) -> bool {
+        self.value(l.abs()) == Some(l > 0)
+    }
+
+    /// Iterate over the satisfied literal of every variable in ascending variable order.
+    pub fn iter(&self) -> impl Iterator<Item = i32> + '_ {
+        (1..=self.num_vars as i32).map(|var| if self.lit(var) { var } else { -var })
+    }
+
+    pub fn as_vec(&self) -> Vec<i32> {
+        self.iter().collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a Model {
+    type Item = i32;
+
+    type IntoIter = impl Iterator<Item = Self::Item> + 'a;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl std::fmt::Debug for Model {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Model").field(&self.as_vec()).finish()
+    }
+}
+
+/// Formats the model as DIMACS value lines, i.e. `v 1 -2 3 ... 0`.
+impl std::fmt::Display for Model {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Keep lines shorter than 80 characters, like most solvers do.
+        const MAX_LINE_LEN: usize = 78;
+
+        let mut line = String::from("v");
+        for lit in self.iter().chain(std::iter::once(0)) {
+            let lit = lit.to_string();
+            if line.len() + 1 + lit.len() > MAX_LINE_LEN {
+                writeln!(f, "{line}")?;
+                line.truncate(1);
+            }
+            line.push(' ');
+            line.push_str(&lit);
+        }
+        write!(f, "{line}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Solver;
+
+    #[test]
+    fn model_outlives_solver_borrow() {
+        let mut solver = Solver::new();
+        solver.add_clause([1, 2]);
+        solver.add_clause([-1]);
+
+        let model = solver.solve().unwrap_sat();
+        assert_eq!(model.value(1), Some(false));
+        assert_eq!(model.value(2), Some(true));
+        assert_eq!(model.value(3), None);
+
+        // The solver can be used again while the model is still alive.
+        solver.add_clause([-2, 3]);
+        let second = solver.solve().unwrap_sat();
+
+        assert!(model.lit(2) && !model.lit(-2));
+        assert_eq!(model.as_vec(), vec![-1, 2]);
+        assert_eq!(second.as_vec(), vec![-1, 2, 3]);
+    }
+
+    #[test]
+    fn display_as_dimacs() {
+        let mut solver = Solver::new();
+        for var in 1..=30 {
+            solver.add_clause([if var % 2 == 0 { var } else { -var }]);
+        }
+        let model = solver.solve().unwrap_sat();
+        let output = model.to_string();
+
+        assert!(output
+            .lines()
+            .all(|line| line.starts_with("v ") && line.len() <= 78));
+        assert!(output.ends_with(" 0"));
+
+        let lits: Vec<i32> = output
+            .split_whitespace()
+            .filter(|token| *token != "v")
+            .map(|lit| lit.parse().unwrap())
+            .collect();
+        assert_eq!(&lits[..lits.len() - 1], model.as_vec().as_slice());
+    }
+}
@@ -1,16 +1,101 @@
+use std::num::NonZeroU32;
+
 use tracing::debug;
 
 /// Implementation of the unit propagation algorithm for two watched literals.
-use super::{clause::ClauseIdx, trail::TrailReason, watch::Watch, Solver};
+use super::{clause::ClauseIdx, trail::TrailReason, watch::Watch, Lit, Solver};
+
+/// After how many propagated literals do we check, if termination was requested.
+const TERMINATE_POLL_INTERVAL: usize = 1 << 12;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum PropagationResult {
     Contradiction(ClauseIdx),
     Done,
+    /// Termination was requested while propagating.
+    Interrupted,
+    /// A clause was falsified on decision level zero.
+    Unsat,
+}
+
+/// Result of propagating constraints, which are not handled by the watched literal scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConstraintPropagationResult {
+    /// The trail wasn't changed.
+    Fixpoint,
+    /// The trail changed, so unit propagation has to continue.
+    Changed,
+    Contradiction(ClauseIdx),
+    /// A constraint is falsified on decision level zero.
+    Unsat,
 }
 
 impl Solver {
+    /// Propagate until a fixpoint is reached for the clauses, the native constraints and the user propagator.
     pub(crate) fn propagate(&mut self) -> PropagationResult {
+        loop {
+            let result = self.propagate_clauses();
+            if result != PropagationResult::Done {
+                return result;
+            }
+
+            let mut result = self.propagate_xors();
+            if result == ConstraintPropagationResult::Fixpoint {
+                result = self.propagate_cardinality();
+            }
+            if result == ConstraintPropagationResult::Fixpoint {
+                result = self.propagate_user();
+            }
+
+            match result {
+                ConstraintPropagationResult::Fixpoint => return PropagationResult::Done,
+                ConstraintPropagationResult::Changed => continue,
+                ConstraintPropagationResult::Contradiction(cls) => {
+                    return PropagationResult::Contradiction(cls)
+                }
+                ConstraintPropagationResult::Unsat => return PropagationResult::Unsat,
+            }
+        }
+    }
+
+    /// Add the falsified clause `conflict` and make sure, that the conflict is on the current decision level.
+    pub(crate) fn add_conflict_clause(
+        &mut self,
+        mut conflict: Vec<Lit>,
+    ) -> ConstraintPropagationResult {
+        debug_assert!(conflict
+            .iter()
+            .all(|&lit| self.trail.is_lit_unsatisfied(lit)));
+
+        let conflict_level = conflict
+            .iter()
+            .filter_map(|&lit| self.trail.get_decision_level(lit))
+            .max()
+            .unwrap_or(0);
+
+        if conflict.len() < 2 || conflict_level == 0 {
+            // The conflict is either on decision level zero or the clause is unit on decision level zero.
+            // Like all explanations of constraints, the clause is redundant.
+            return if self.integrate_clause(conflict, Some(NonZeroU32::MIN)) {
+                ConstraintPropagationResult::Changed
+            } else {
+                ConstraintPropagationResult::Unsat
+            };
+        }
+
+        // Conflict analysis expects at least one literal on the current decision level.
+        self.backtrack(conflict_level);
+
+        let glue = Self::calculate_ldb_from_lits(&self.trail, &conflict);
+        self.order_for_watching(&mut conflict);
+        let cls_idx = self.attach_clause(&conflict, Some(glue));
+
+        self.stats.contradictions += 1;
+        self.stats.contradiction_since_last_garbage_collections += 1;
+        ConstraintPropagationResult::Contradiction(cls_idx)
+    }
+
+    fn propagate_clauses(&mut self) -> PropagationResult {
         debug!(
             "starting unit propagation {} (at {})",
             self.trail.fmt_trail(),
@@ -19,6 +104,15 @@ impl Solver {
         let mut trail_pos = self.unpropagated_lit_pos;
 
         while let Some(&trail_elem) = self.trail.get(trail_pos) {
+            self.propagations_since_poll += 1;
+            if self.propagations_since_poll >= TERMINATE_POLL_INTERVAL {
+                self.propagations_since_poll = 0;
+                if self.terminate_requested() {
+                    self.unpropagated_lit_pos = trail_pos;
+                    return PropagationResult::Interrupted;
+                }
+            }
+
             let lit = trail_elem.lit;
             debug!(
                 "propagating {lit}, with trail {trail}",
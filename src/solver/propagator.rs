@@ -0,0 +1,290 @@
+use tracing::debug;
+
+use super::{
+    data::VarVec, propagate::ConstraintPropagationResult, trail::TrailReason, Lit, Solver, Var,
+};
+
+/// Interface for external propagators, which add domain specific reasoning to the search,
+/// modeled after IPASIR-UP.
+///
+/// Literals are passed as DIMACS style `i32`.
+/// The solver only notifies the propagator about assignments of variables which were registered with
+/// [`Solver::add_observed_var`].
+pub trait UserPropagator: Send {
+    /// Observed literals were assigned, in the order of the trail.
+    fn notify_assignment(&mut self, _lits: &[i32]) {}
+
+    /// A new decision level was started. The following assignments belong to this level.
+    fn notify_new_decision_level(&mut self) {}
+
+    /// The solver backtracked to `new_level`.
+    /// All assignments on higher levels were removed.
+    fn notify_backtrack(&mut self, _new_level: u32) {}
+
+    /// Literals which are implied by the current assignment.
+    /// This is called every time unit propagation reached a fixpoint.
+    ///
+    /// A returned literal which is already falsified is a conflict.
+    fn propagate(&mut self) -> Vec<i32> {
+        Vec::new()
+    }
+
+    /// Explain why `propagated_lit` was implied.
+    /// The returned clause has to contain `propagated_lit` and otherwise only unsatisfied literals.
+    /// Reasons are only requested when the solver needs them during conflict analysis.
+    fn add_reason_clause(&mut self, propagated_lit: i32) -> Vec<i32>;
+
+    /// Check a complete assignment of all variables.
+    /// If the model is rejected, at least one clause falsified by the model has to be provided
+    /// through [`UserPropagator::add_external_clause`]. Otherwise, the solver gives up and
+    /// returns [`Result::Unknown`](crate::Result::Unknown).
+    fn check_model(&mut self, _model: &[i32]) -> bool {
+        true
+    }
+
+    /// Clauses which should be added to the formula.
+    /// This is polled until it returns `None` every time unit propagation reached a fixpoint and
+    /// after a model was rejected.
+    fn add_external_clause(&mut self) -> Option<Vec<i32>> {
+        None
+    }
+
+    /// Suggest the next decision literal.
+    /// Suggestions of already assigned literals are ignored.
+    fn decide(&mut self) -> Option<i32> {
+        None
+    }
+}
+
+pub(crate) struct PropagatorState {
+    propagator: Box<dyn UserPropagator>,
+
+    observed: VarVec<bool>,
+
+    /// Position on the trail up to which the propagator was notified.
+    notified_pos: usize,
+
+    /// Decision level which the propagator knows of.
+    notified_level: u32,
+
+    /// Buffer of literals for the next assignment notification.
+    notification: Vec<i32>,
+}
+
+/// Verdict of the user propagator on a complete assignment.
+pub(crate) enum ModelCheck {
+    Accepted,
+    /// The external clauses provided by the propagator changed the trail.
+    Rejected,
+    /// The model was rejected, but no clause falsified by it was provided.
+    ContractViolated,
+}
+
+impl Solver {
+    /// Connect a user propagator. A previously connected propagator is replaced.
+    pub fn connect_propagator(&mut self, propagator: Box<dyn UserPropagator>) {
+        self.backtrack(0);
+
+        let mut observed = VarVec::new();
+        observed.resize(self.trail.total_vars(), false);
+
+        self.user_propagator = Some(PropagatorState {
+            propagator,
+            observed,
+            notified_pos: 0,
+            notified_level: 0,
+            notification: Vec::new(),
+        });
+    }
+
+    /// Disconnect the user propagator and return it.
+    pub fn disconnect_propagator(&mut self) -> Option<Box<dyn UserPropagator>> {
+        self.backtrack(0);
+        self.user_propagator.take().map(|state| state.propagator)
+    }
+
+    /// Notify the connected propagator about assignments of `var`.
+    /// Panics if no propagator is connected.
+    pub fn add_observed_var(&mut self, var: i32) {
+        let var = Var::new(var);
+        self.trail.expand(var);
+        self.watches.expand(-Lit::from(var), Vec::new());
+
+        self.backtrack(0);
+
+        let state = self
+            .user_propagator
+            .as_mut()
+            .expect("A propagator has to be connected to observe variables.");
+        state.observed.expand(var, false);
+        if state.observed[var] {
+            return;
+        }
+        state.observed[var] = true;
+
+        // Make sure the propagator learns about a fixed assignment of the variable, which it
+        // has already been notified about.
+        let already_notified = (0..state.notified_pos)
+            .filter_map(|pos| self.trail.get(pos))
+            .find(|trail_elem| trail_elem.lit.var() == var);
+        if let Some(trail_elem) = already_notified {
+            state.notification.push(trail_elem.lit.into());
+        }
+    }
+
+    /// Notify the propagator about all assignments since the last notification.
+    fn notify_propagator(&mut self) {
+        let Some(state) = &mut self.user_propagator else {
+            return;
+        };
+
+        while let Some(trail_elem) = self.trail.get(state.notified_pos) {
+            state.notified_pos += 1;
+
+            if trail_elem.reason == TrailReason::Decision {
+                if !state.notification.is_empty() {
+                    state.propagator.notify_assignment(&state.notification);
+                    state.notification.clear();
+                }
+                state.notified_level += 1;
+                state.propagator.notify_new_decision_level();
+            }
+
+            let var = trail_elem.lit.var();
+            if var.get() as usize <= state.observed.len() && state.observed[var] {
+                state.notification.push(trail_elem.lit.into());
+            }
+        }
+
+        if !state.notification.is_empty() {
+            state.propagator.notify_assignment(&state.notification);
+            state.notification.clear();
+        }
+    }
+
+    /// Called whenever the solver backtracks to `lvl`.
+    pub(crate) fn propagator_backtrack(&mut self, lvl: u32) {
+        let Some(state) = &mut self.user_propagator else {
+            return;
+        };
+
+        state.notified_pos = state.notified_pos.min(self.trail.assigned_vars());
+        if state.notified_level > lvl {
+            state.notified_level = lvl;
+            state.propagator.notify_backtrack(lvl);
+        }
+    }
+
+    /// Add external clauses and ask the propagator for implied literals.
+    pub(crate) fn propagate_user(&mut self) -> ConstraintPropagationResult {
+        if self.user_propagator.is_none() {
+            return ConstraintPropagationResult::Fixpoint;
+        }
+
+        if let Some(result) = self.add_external_clauses() {
+            return result;
+        }
+
+        self.notify_propagator();
+        let state = self.user_propagator.as_mut().unwrap();
+        let lits = state.propagator.propagate();
+
+        let mut result = ConstraintPropagationResult::Fixpoint;
+        for lit in lits {
+            let lit = Lit::new(lit);
+            self.trail.expand(lit.var());
+            self.watches.expand(-Lit::from(lit.var()), Vec::new());
+
+            match self.trail.get_lit_assignment(lit) {
+                Some(true) => {}
+                None => {
+                    debug!("user propagator assigns {lit}");
+                    self.trail.assign_lit(lit, TrailReason::External);
+                    self.stats.propagations += 1;
+                    result = ConstraintPropagationResult::Changed;
+                }
+                Some(false) => {
+                    debug!("user propagator found contradiction with {lit}");
+                    let reason = self.external_reason(lit);
+                    return self.add_conflict_clause(reason);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Integrate all clauses the propagator wants to add.
+    /// Returns `None`, if this didn't change the trail.
+    fn add_external_clauses(&mut self) -> Option<ConstraintPropagationResult> {
+        let trail_len = self.trail.assigned_vars();
+        let decision_level = self.trail.current_decision_level();
+
+        while let Some(clause) = self
+            .user_propagator
+            .as_mut()
+            .and_then(|state| state.propagator.add_external_clause())
+        {
+            debug!("adding external clause {clause:?}");
+            let clause = clause.into_iter().map(Lit::new).collect();
+            if !self.integrate_clause(clause, None) {
+                return Some(ConstraintPropagationResult::Unsat);
+            }
+        }
+
+        let changed = trail_len != self.trail.assigned_vars()
+            || decision_level != self.trail.current_decision_level();
+        changed.then_some(ConstraintPropagationResult::Changed)
+    }
+
+    /// Request the reason clause for a literal propagated by the user propagator.
+    pub(crate) fn external_reason(&mut self, lit: Lit) -> Vec<Lit> {
+        let state = self
+            .user_propagator
+            .as_mut()
+            .expect("External propagations require a propagator");
+
+        state
+            .propagator
+            .add_reason_clause(lit.into())
+            .into_iter()
+            .map(Lit::new)
+            .collect()
+    }
+
+    /// Ask the propagator for a decision.
+    pub(crate) fn user_decide(&mut self) -> Option<Lit> {
+        self.user_propagator.as_ref()?;
+
+        self.notify_propagator();
+        let state = self.user_propagator.as_mut().unwrap();
+        let lit = Lit::new(state.propagator.decide()?);
+
+        (lit.var().get() as usize <= self.trail.total_vars() && self.trail.is_lit_unassigned(lit))
+            .then_some(lit)
+    }
+
+    /// Let the propagator check the complete assignment.
+    pub(crate) fn user_check_model(&mut self) -> ModelCheck {
+        if self.user_propagator.is_none() {
+            return ModelCheck::Accepted;
+        }
+
+        self.notify_propagator();
+        let model = self.extract_model().as_vec();
+        let state = self.user_propagator.as_mut().unwrap();
+
+        if state.propagator.check_model(&model) {
+            return ModelCheck::Accepted;
+        }
+
+        debug!("user propagator rejected model");
+        match self.add_external_clauses() {
+            Some(_) => ModelCheck::Rejected,
+            None => {
+                debug!("user propagator rejected a model without adding a falsified clause");
+                ModelCheck::ContractViolated
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use tracing::debug;
+
+use super::{config::Phase, Solver};
+
+/// Flips per clause of a local search run, whose best assignment becomes the saved phases.
+const WALK_FLIPS_PER_CLAUSE: u64 = 20;
+
+/// Phases, which replace the saved phases.
+#[derive(Debug, Clone, Copy)]
+enum Rephase {
+    /// The default [`Phase`] of the configuration.
+    Original,
+    /// The opposite of the default phase.
+    Inverted,
+    /// The phases of the largest conflict-free trail since the last rephasing.
+    Best,
+    /// The best assignment of a local search run.
+    Walk,
+}
+
+const CYCLE: [Rephase; 4] = [
+    Rephase::Original,
+    Rephase::Inverted,
+    Rephase::Best,
+    Rephase::Walk,
+];
+
+#[derive(Default)]
+pub(crate) struct RephaseState {
+    /// Conflicts since the last rephasing.
+    conflicts: u64,
+    /// Number of rephasings so far, which determines the next phases of the cycle.
+    count: u64,
+}
+
+impl Solver {
+    /// Count a conflict and replace the saved phases, once the rephasing interval of the
+    /// [`Config`](super::Config) has passed. Backtracks to decision level zero.
+    pub(crate) fn maybe_rephase(&mut self) {
+        let Some(interval) = self.config.rephase_interval else {
+            return;
+        };
+
+        self.rephase_state.conflicts += 1;
+        if self.rephase_state.conflicts < interval * (self.rephase_state.count + 1) {
+            return;
+        }
+
+        let rephase = CYCLE[(self.rephase_state.count % CYCLE.len() as u64) as usize];
+        debug!(
+            "rephasing with {rephase:?} phases after {} conflicts",
+            self.rephase_state.conflicts
+        );
+        self.rephase_state.conflicts = 0;
+        self.rephase_state.count += 1;
+        self.stats.rephases += 1;
+        self.backtrack(0);
+
+        match rephase {
+            Rephase::Original => self.decider.reset_saved_phases(None),
+            Rephase::Inverted => self.decider.reset_saved_phases(match self.config.phase {
+                Phase::Positive => Some(false),
+                Phase::Negative => Some(true),
+                Phase::Random => None,
+            }),
+            Rephase::Best => self.decider.restore_best_phases(),
+            Rephase::Walk => {
+                let num_clauses = self
+                    .clause_db
+                    .iter()
+                    .filter(|clause| clause.glue().is_none())
+                    .count() as u64;
+                self.run_walker(WALK_FLIPS_PER_CLAUSE * num_clauses);
+            }
+        }
+        self.decider.reset_best();
+    }
+}
@@ -0,0 +1,125 @@
+use tracing::debug;
+
+use super::{config::Restarts, Solver};
+
+/// Smoothing factors of the exponential moving averages of the glue of learned clauses.
+const FAST_GLUE_ALPHA: f64 = 1.0 / 32.0;
+const SLOW_GLUE_ALPHA: f64 = 1.0 / 4096.0;
+
+/// Glucose restarts happen, once the fast average exceeds the slow average by this factor.
+const GLUCOSE_MARGIN: f64 = 1.1;
+
+/// Minimal number of conflicts between two Glucose restarts.
+const GLUCOSE_MIN_CONFLICTS: u64 = 2;
+
+#[derive(Default)]
+pub(crate) struct RestartState {
+    /// Conflicts since the last restart.
+    conflicts: u64,
+    /// Index of the current element of the Luby sequence, starting at zero.
+    luby_index: u64,
+    /// Moving averages of the glue of learned clauses, `None` before the first conflict.
+    fast_glue: Option<f64>,
+    slow_glue: Option<f64>,
+}
+
+impl RestartState {
+    /// Update the glue averages with a newly learned clause.
+    pub(crate) fn learned(&mut self, glue: u32) {
+        let update = |average: &mut Option<f64>, alpha: f64| {
+            let glue = glue as f64;
+            *average = Some(average.map_or(glue, |average| average + alpha * (glue - average)));
+        };
+        update(&mut self.fast_glue, FAST_GLUE_ALPHA);
+        update(&mut self.slow_glue, SLOW_GLUE_ALPHA);
+    }
+
+    /// Start over with the restart policy of a new search mode.
+    pub(crate) fn reset(&mut self) {
+        self.conflicts = 0;
+        self.luby_index = 0;
+    }
+}
+
+/// Element `idx` of the Luby sequence 1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8, ...
+fn luby(mut idx: u64) -> u64 {
+    // Find the complete subsequence of length `2^k - 1`, which contains the element.
+    let (mut size, mut k) = (1, 0);
+    while size < idx + 1 {
+        size = 2 * size + 1;
+        k += 1;
+    }
+    while size - 1 != idx {
+        size = (size - 1) / 2;
+        k -= 1;
+        idx %= size;
+    }
+    1 << k
+}
+
+impl Solver {
+    /// Count a conflict and backtrack to decision level zero, once the restart policy of the
+    /// [`Config`](super::Config) or the search mode asks for it. Learned clauses are kept.
+    pub(crate) fn maybe_restart(&mut self) {
+        let policy = self.restart_policy();
+        let state = &mut self.restart_state;
+        state.conflicts += 1;
+        let restart = match policy {
+            Restarts::Never => false,
+            Restarts::Luby { unit } => state.conflicts >= unit * luby(state.luby_index),
+            Restarts::Glucose => match (state.fast_glue, state.slow_glue) {
+                (Some(fast), Some(slow)) => {
+                    state.conflicts >= GLUCOSE_MIN_CONFLICTS && fast > GLUCOSE_MARGIN * slow
+                }
+                _ => false,
+            },
+        };
+        if !restart {
+            return;
+        }
+
+        debug!(
+            "restarting after {} conflicts",
+            self.restart_state.conflicts
+        );
+        self.restart_state.conflicts = 0;
+        self.restart_state.luby_index += 1;
+        self.stats.restarts += 1;
+        if let Some(stats) = self.mode_stats() {
+            stats.restarts += 1;
+        }
+        self.backtrack(0);
+        self.decider.reset_target();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::config::Config;
+
+    #[test]
+    fn glucose_restarts_when_the_glue_rises() {
+        let mut solver = Solver::with_config(Config {
+            restarts: Restarts::Glucose,
+            ..Config::default()
+        });
+        for _ in 0..100 {
+            solver.restart_state.learned(3);
+            solver.maybe_restart();
+        }
+        assert_eq!(solver.stats.restarts, 0);
+
+        for _ in 0..2 {
+            solver.restart_state.learned(10);
+            solver.maybe_restart();
+        }
+        assert_eq!(solver.stats.restarts, 1);
+    }
+
+    #[test]
+    fn luby_sequence() {
+        let sequence: Vec<u64> = (0..15).map(luby).collect();
+        assert_eq!(sequence, [1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use tracing::debug;
+
+use super::Solver;
+
+/// Handle to request the termination of a running [`Solver::solve`] call, possibly from another thread.
+///
+/// Once terminated, every call to `solve` returns [`Result::Unknown`](super::Result::Unknown)
+/// until the handle is [reset](TerminateHandle::reset).
+#[derive(Clone, Default, Debug)]
+pub struct TerminateHandle(Arc<AtomicBool>);
+
+impl TerminateHandle {
+    pub fn terminate(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+pub(crate) type TerminateCallback = Box<dyn FnMut() -> bool + Send>;
+
+impl Solver {
+    /// Get a handle which can be used to stop the solver from another thread.
+    pub fn terminate_handle(&self) -> TerminateHandle {
+        self.terminate_handle.clone()
+    }
+
+    /// Set a callback, which is polled periodically during search.
+    /// If it returns true, the solver stops and returns `Result::Unknown`.
+    pub fn set_terminate(&mut self, callback: impl FnMut() -> bool + Send + 'static) {
+        self.terminate_callback = Some(Box::new(callback));
+    }
+
+    pub fn clear_terminate(&mut self) {
+        self.terminate_callback = None;
+    }
+
+    /// Check whether termination was requested either through the handle or the callback.
+    pub(crate) fn terminate_requested(&mut self) -> bool {
+        let requested = self.terminate_handle.is_terminated()
+            || self
+                .terminate_callback
+                .as_mut()
+                .is_some_and(|callback| callback());
+
+        if requested {
+            debug!("Termination requested.");
+        }
+        requested
+    }
+}
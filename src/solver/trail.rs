@@ -16,6 +16,18 @@ pub(crate) enum TrailReason {
 
     /// Axiomatic literal. These are generated when the user is supplying a unit clause.
     Axiom,
+
+    /// Literal was propagated by the [`UserPropagator`](super::UserPropagator).
+    /// The reason clause is requested lazily during conflict analysis.
+    External,
+
+    /// Literal was propagated by the xor constraints.
+    /// The reason clause is derived lazily during conflict analysis.
+    Xor,
+
+    /// Literal was propagated by a cardinality constraint.
+    /// The reason clause is derived lazily during conflict analysis.
+    Cardinality,
 }
 
 impl TrailReason {
@@ -24,7 +36,11 @@ impl TrailReason {
     pub(crate) fn get_cls_idx_mut(&mut self) -> &mut ClauseIdx {
         match self {
             TrailReason::Propagated { cls } => cls,
-            TrailReason::Decision | TrailReason::Axiom => {
+            TrailReason::Decision
+            | TrailReason::Axiom
+            | TrailReason::External
+            | TrailReason::Xor
+            | TrailReason::Cardinality => {
                 panic!("`self` is not the `Propagated` variant.")
             }
         }
@@ -163,7 +179,7 @@ impl Trail {
         }
         let decision_level = self.current_decision_level();
         self.assignment
-            .assign_lit(lit, decision_level as u32, reason);
+            .assign_lit(lit, decision_level, reason, self.trail.len() - 1);
     }
 
     pub fn trail(&self) -> &[TrailElement] {
@@ -187,15 +203,23 @@ impl Trail {
             .map(|data| data.decision_level)
     }
 
-    /// Get the reason clause of a propagated literal.
-    /// Panics if lit is not a propagated clause.
-    pub fn get_reason_cls(&self, lit: Lit) -> ClauseIdx {
-        let reason = self.assignment.get_data(lit).unwrap().reason;
-        if let TrailReason::Propagated { cls } = reason {
-            cls
-        } else {
-            panic!("Literal {lit} wasn't propagated");
-        }
+    /// Get the reason of an assigned literal.
+    /// Panics if lit is not assigned.
+    pub fn get_reason(&self, lit: Lit) -> TrailReason {
+        self.assignment.get_data(lit).unwrap().reason
+    }
+
+    /// Replace the reason of an assigned literal.
+    pub(crate) fn set_reason(&mut self, lit: Lit, reason: TrailReason) {
+        let data = self
+            .assignment
+            .get_data_mut(lit)
+            .expect("literal has to be assigned");
+        data.reason = reason;
+
+        let trail_elem = &mut self.trail[data.trail_pos];
+        debug_assert_eq!(trail_elem.lit, lit);
+        trail_elem.reason = reason;
     }
 
     // Backtrack assignments such that the literals with the decision level `lvl` are last on the trail (i.e. are not removed.)
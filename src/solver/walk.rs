@@ -0,0 +1,244 @@
+use tracing::debug;
+
+use super::{
+    data::{LitVec, VarVec},
+    decide::splitmix64,
+    propagate::PropagationResult,
+    Lit, Model, Solver, Var,
+};
+
+/// Base of the ProbSAT flip probability `CB^-break`, the value recommended for random 3-SAT.
+const CB: f64 = 2.5;
+
+/// ProbSAT local search over a fixed set of clauses.
+pub(crate) struct Walker {
+    clauses: Vec<Vec<Lit>>,
+    /// Indices of the clauses containing a literal.
+    occurrences: LitVec<Vec<usize>>,
+    /// Number of satisfied literals of every clause.
+    num_true: Vec<u32>,
+    /// Indices of the falsified clauses.
+    unsat: Vec<usize>,
+    /// Position of every falsified clause in `unsat`.
+    unsat_pos: Vec<Option<usize>>,
+    values: VarVec<bool>,
+    /// Assignment with the fewest falsified clauses seen so far.
+    best: VarVec<bool>,
+    best_unsat: usize,
+    /// `CB^-break` for every possible break count.
+    probabilities: Vec<f64>,
+    rng: u64,
+}
+
+impl Walker {
+    fn new(clauses: Vec<Vec<Lit>>, values: VarVec<bool>, seed: u64) -> Self {
+        let mut occurrences = LitVec::new();
+        for (idx, clause) in clauses.iter().enumerate() {
+            for &lit in clause {
+                occurrences.expand(Lit::from(lit.var()), Vec::new());
+                occurrences.expand(-Lit::from(lit.var()), Vec::new());
+                occurrences[lit].push(idx);
+            }
+        }
+        let max_occurrences = clauses.len();
+
+        let mut walker = Self {
+            num_true: vec![0; clauses.len()],
+            unsat: Vec::new(),
+            unsat_pos: vec![None; clauses.len()],
+            clauses,
+            occurrences,
+            best: values.clone(),
+            values,
+            best_unsat: usize::MAX,
+            probabilities: (0..=max_occurrences)
+                .map(|breaks| CB.powi(-(breaks as i32)))
+                .collect(),
+            rng: seed,
+        };
+
+        for idx in 0..walker.clauses.len() {
+            let num_true = walker.clauses[idx]
+                .iter()
+                .filter(|&&lit| walker.is_true(lit))
+                .count();
+            walker.num_true[idx] = num_true as u32;
+            if num_true == 0 {
+                walker.mark_unsat(idx);
+            }
+        }
+        walker.update_best();
+        walker
+    }
+
+    fn is_true(&self, lit: Lit) -> bool {
+        self.values[lit.var()] == lit.is_pos()
+    }
+
+    fn mark_unsat(&mut self, idx: usize) {
+        self.unsat_pos[idx] = Some(self.unsat.len());
+        self.unsat.push(idx);
+    }
+
+    fn mark_sat(&mut self, idx: usize) {
+        let pos = self.unsat_pos[idx].take().unwrap();
+        self.unsat.swap_remove(pos);
+        if let Some(&moved) = self.unsat.get(pos) {
+            self.unsat_pos[moved] = Some(pos);
+        }
+    }
+
+    fn update_best(&mut self) {
+        if self.unsat.len() < self.best_unsat {
+            self.best_unsat = self.unsat.len();
+            self.best.clone_from(&self.values);
+        }
+    }
+
+    /// Number of clauses, which become falsified, if `lit` is made true.
+    fn break_count(&self, lit: Lit) -> usize {
+        self.occurrences[-lit]
+            .iter()
+            .filter(|&&idx| self.num_true[idx] == 1)
+            .count()
+    }
+
+    /// Make the falsified literal `lit` true.
+    fn flip(&mut self, lit: Lit) {
+        self.values[lit.var()] = lit.is_pos();
+
+        for i in 0..self.occurrences[lit].len() {
+            let idx = self.occurrences[lit][i];
+            self.num_true[idx] += 1;
+            if self.num_true[idx] == 1 {
+                self.mark_sat(idx);
+            }
+        }
+        for i in 0..self.occurrences[-lit].len() {
+            let idx = self.occurrences[-lit][i];
+            self.num_true[idx] -= 1;
+            if self.num_true[idx] == 0 {
+                self.mark_unsat(idx);
+            }
+        }
+    }
+
+    /// Flip variables of random falsified clauses, preferring literals with small break counts,
+    /// until all clauses are satisfied or `max_flips` is reached. Returns the number of flips.
+    fn run(&mut self, max_flips: u64) -> u64 {
+        let mut flips = 0;
+        let mut weights = Vec::new();
+        while !self.unsat.is_empty() && flips < max_flips {
+            let pick = splitmix64(&mut self.rng) % self.unsat.len() as u64;
+            let clause = &self.clauses[self.unsat[pick as usize]];
+
+            weights.clear();
+            weights.extend(
+                clause
+                    .iter()
+                    .map(|&lit| self.probabilities[self.break_count(lit)]),
+            );
+            let total: f64 = weights.iter().sum();
+            let mut threshold =
+                (splitmix64(&mut self.rng) >> 11) as f64 / (1u64 << 53) as f64 * total;
+            let mut choice = clause.len() - 1;
+            for (i, &weight) in weights.iter().enumerate() {
+                if threshold < weight {
+                    choice = i;
+                    break;
+                }
+                threshold -= weight;
+            }
+
+            let lit = clause[choice];
+            self.flip(lit);
+            flips += 1;
+            self.update_best();
+        }
+        flips
+    }
+}
+
+impl Solver {
+    /// Run local search on the irredundant clauses, starting from the preferred phases, and save
+    /// the best assignment found as the phases of the unassigned variables.
+    /// Assigned variables keep their value, so this is meant to be called on decision level zero.
+    pub(crate) fn run_walker(&mut self, max_flips: u64) -> Walker {
+        let num_vars = self.trail.total_vars();
+        self.decider.grow(num_vars, self.config.seed != 0);
+
+        let mut values = VarVec::with_size(num_vars, false);
+        for var in 1..=num_vars {
+            let var = Var::new(var as i32);
+            values[var] = match self.trail.get_lit_assignment(Lit::from(var)) {
+                Some(value) => value,
+                None => self.preferred_phase(var),
+            };
+        }
+
+        let clauses: Vec<Vec<Lit>> = self
+            .clause_db
+            .iter()
+            .filter(|clause| clause.glue().is_none() && !clause.flags().is_garbage())
+            .filter(|clause| !self.trail.is_clause_satisfied(*clause))
+            .map(|clause| {
+                let mut lits = clause.lits().to_vec();
+                lits.retain(|&lit| self.trail.is_lit_unassigned(lit));
+                lits
+            })
+            .filter(|lits| !lits.is_empty())
+            .collect();
+
+        let seed = self.decider.next_random();
+        let mut walker = Walker::new(clauses, values, seed);
+        let flips = walker.run(max_flips);
+        debug!(
+            "local search with {flips} flips left {} of {} clauses falsified",
+            walker.best_unsat,
+            walker.clauses.len()
+        );
+        self.stats.walks += 1;
+        self.stats.walk_flips += flips;
+
+        for (var, &value) in walker.best.iter_with_var() {
+            if self.trail.is_lit_unassigned(Lit::from(var)) {
+                self.decider.save_phase(var, value);
+            }
+        }
+        walker
+    }
+
+    /// Search for a model with ProbSAT local search on the irredundant clauses, flipping at most
+    /// `max_flips` variables. The best assignment is saved as the phases of the CDCL search, see
+    /// [`Config::phase_saving`](super::Config::phase_saving).
+    ///
+    /// Local search can't show that a formula is unsat, so `None` only means that no model was
+    /// found.
+    /// Panics if the solver has xor or cardinality constraints or a user propagator.
+    pub fn walk(&mut self, max_flips: u64) -> Option<Model> {
+        assert!(
+            self.xor_db.is_empty() && self.cardinality_db.is_empty(),
+            "local search only supports clauses"
+        );
+        assert!(
+            self.user_propagator.is_none(),
+            "local search doesn't support a user propagator"
+        );
+
+        self.backtrack(0);
+        if self.trivially_unsat || !self.import_clauses() {
+            return None;
+        }
+        match self.propagate() {
+            PropagationResult::Done => {}
+            PropagationResult::Interrupted => return None,
+            PropagationResult::Contradiction(_) | PropagationResult::Unsat => {
+                self.trivially_unsat = true;
+                return None;
+            }
+        }
+
+        let walker = self.run_walker(max_flips);
+        (walker.best_unsat == 0).then(|| Model::from_values(&walker.best))
+    }
+}
@@ -0,0 +1,557 @@
+use tracing::debug;
+
+use super::{
+    data::VarVec, propagate::ConstraintPropagationResult, trail::Trail, trail::TrailReason, Lit,
+    Solver, Var,
+};
+
+/// A parity constraint `x_1 ⊕ ... ⊕ x_n = rhs`.
+#[derive(Debug, Clone)]
+struct XorConstraint {
+    vars: Vec<Var>,
+    rhs: bool,
+}
+
+/// Fixed size bit vector used for the rows of the Gauss-Jordan matrix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bits(Vec<u64>);
+
+impl Bits {
+    fn new(len: usize) -> Self {
+        Bits(vec![0; len.div_ceil(64)])
+    }
+
+    fn get(&self, idx: usize) -> bool {
+        self.0[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    fn set(&mut self, idx: usize) {
+        self.0[idx / 64] |= 1 << (idx % 64);
+    }
+
+    fn xor_assign(&mut self, other: &Bits) {
+        for (word, other) in self.0.iter_mut().zip(&other.0) {
+            *word ^= other;
+        }
+    }
+
+    fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(idx, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| idx * 64 + bit)
+        })
+    }
+}
+
+/// Row of the Gauss-Jordan matrix, which is a sum of xor constraints.
+#[derive(Debug, Clone)]
+struct Row {
+    columns: Bits,
+    rhs: bool,
+    /// Basic column of this row, which doesn't appear in any other row.
+    pivot: usize,
+    /// Watched column besides the pivot.
+    watch: Option<usize>,
+}
+
+impl Row {
+    fn new(num_columns: usize, rhs: bool) -> Self {
+        Row {
+            columns: Bits::new(num_columns),
+            rhs,
+            pivot: 0,
+            watch: None,
+        }
+    }
+
+    fn add(&mut self, other: &Row) {
+        self.columns.xor_assign(&other.columns);
+        self.rhs ^= other.rhs;
+    }
+}
+
+/// Add the row at `source` to the row at `target`.
+fn add_row(rows: &mut [Row], target: usize, source: usize) {
+    debug_assert_ne!(target, source);
+    if target < source {
+        let (head, tail) = rows.split_at_mut(source);
+        head[target].add(&tail[0]);
+    } else {
+        let (head, tail) = rows.split_at_mut(target);
+        tail[0].add(&head[source]);
+    }
+}
+
+/// Transform `rows` into reduced row echelon form, set the pivots and return the rank.
+/// Rows at positions after the rank don't contain any variable.
+fn gauss_jordan(rows: &mut [Row], num_columns: usize) -> usize {
+    let mut rank = 0;
+
+    for column in 0..num_columns {
+        if rank == rows.len() {
+            break;
+        }
+        let Some(pivot) = (rank..rows.len()).find(|&row| rows[row].columns.get(column)) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        rows[rank].pivot = column;
+
+        for row in 0..rows.len() {
+            if row != rank && rows[row].columns.get(column) {
+                add_row(rows, row, rank);
+            }
+        }
+
+        rank += 1;
+    }
+
+    rank
+}
+
+/// Storage for xor constraints, which are propagated using Gauss-Jordan elimination instead of
+/// watched literals.
+///
+/// The matrix is eliminated once and then kept in reduced row echelon form, such that the basic
+/// column of each row is unassigned as long as the row has unassigned columns. Each row watches its
+/// pivot and one other unassigned column, so an assignment only visits the rows watching it. Row
+/// operations don't depend on the assignment, so on backtracking only the processed trail position
+/// is undone.
+#[derive(Default)]
+pub(crate) struct XorDB {
+    constraints: Vec<XorConstraint>,
+
+    /// Column of each variable in the Gauss-Jordan matrix.
+    columns: VarVec<Option<usize>>,
+
+    /// Variable of each column.
+    vars: Vec<Var>,
+
+    /// The eliminated matrix.
+    rows: Vec<Row>,
+
+    /// Constraints were added after the matrix was eliminated.
+    outdated: bool,
+
+    /// Rows watching each column. Rows, which moved their watch, are removed lazily.
+    watches: Vec<Vec<usize>>,
+
+    /// Rows, which were changed by row operations, and whose watches have to be updated.
+    changed_rows: Vec<usize>,
+
+    /// Position on the trail, up to which assignments are processed.
+    processed_pos: usize,
+
+    /// The other variables of the row, which implied the assignment of a variable.
+    reasons: VarVec<Vec<Var>>,
+}
+
+impl XorDB {
+    fn add(&mut self, xor: XorConstraint) {
+        for &var in &xor.vars {
+            self.columns.expand(var, None);
+            if self.columns[var].is_none() {
+                self.columns[var] = Some(self.vars.len());
+                self.vars.push(var);
+            }
+        }
+        self.constraints.push(xor);
+        self.outdated = true;
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    fn column(&self, var: Var) -> Option<usize> {
+        if var.get() as usize > self.columns.len() {
+            return None;
+        }
+        self.columns[var]
+    }
+
+    fn value(&self, trail: &Trail, column: usize) -> Option<bool> {
+        trail.get_lit_assignment(Lit::from(self.vars[column]))
+    }
+
+    fn watches_column(&self, row: usize, column: usize) -> bool {
+        self.rows[row].pivot == column || self.rows[row].watch == Some(column)
+    }
+
+    fn set_watch(&mut self, row: usize, watch: Option<usize>) {
+        if self.rows[row].watch != watch {
+            self.rows[row].watch = watch;
+            if let Some(column) = watch {
+                self.watches[column].push(row);
+            }
+        }
+    }
+
+    /// Eliminate the matrix of all constraints from scratch.
+    /// Returns false, if the constraints are contradictory.
+    fn eliminate(&mut self, trail: &Trail) -> bool {
+        let num_columns = self.vars.len();
+        let mut rows: Vec<Row> = self
+            .constraints
+            .iter()
+            .map(|xor| {
+                let mut row = Row::new(num_columns, xor.rhs);
+                for &var in &xor.vars {
+                    row.columns.set(self.columns[var].unwrap());
+                }
+                row
+            })
+            .collect();
+
+        let rank = gauss_jordan(&mut rows, num_columns);
+        let contradictory = rows[rank..].iter().any(|row| row.rhs);
+        rows.truncate(rank);
+
+        self.watches = vec![Vec::new(); num_columns];
+        for (idx, row) in rows.iter().enumerate() {
+            self.watches[row.pivot].push(idx);
+        }
+        self.rows = rows;
+        self.outdated = false;
+
+        // All rows are checked against the current assignment.
+        self.changed_rows = (0..rank).collect();
+        self.processed_pos = trail.assigned_vars();
+
+        !contradictory
+    }
+
+    /// Make `column` the basic column of `row` by eliminating it from all other rows.
+    fn make_pivot(&mut self, row: usize, column: usize) {
+        for other in 0..self.rows.len() {
+            if other != row && self.rows[other].columns.get(column) {
+                add_row(&mut self.rows, other, row);
+                self.changed_rows.push(other);
+            }
+        }
+
+        self.rows[row].pivot = column;
+        self.watches[column].push(row);
+        if self.rows[row].watch == Some(column) {
+            self.rows[row].watch = None;
+        }
+    }
+
+    /// Restore the invariants of `row` after an assignment or a row operation.
+    /// The pivot is propagated, if it is the last unassigned column. Returns the variables of the
+    /// row, if it is falsified.
+    fn update_row(&mut self, row: usize, trail: &mut Trail) -> Option<Vec<Var>> {
+        if self.value(trail, self.rows[row].pivot).is_some() {
+            let unassigned = self.rows[row]
+                .columns
+                .ones()
+                .find(|&column| self.value(trail, column).is_none());
+            if let Some(column) = unassigned {
+                self.make_pivot(row, column);
+            }
+        }
+
+        let pivot = self.rows[row].pivot;
+        let is_unassigned = |column: usize| column != pivot && self.value(trail, column).is_none();
+        let watch = self.rows[row]
+            .watch
+            .filter(|&column| self.rows[row].columns.get(column) && is_unassigned(column))
+            .or_else(|| {
+                self.rows[row]
+                    .columns
+                    .ones()
+                    .find(|&column| is_unassigned(column))
+            });
+
+        if watch.is_some() {
+            self.set_watch(row, watch);
+            return None;
+        }
+
+        // Watch the column, which is unassigned first on backtracking.
+        let latest = self.rows[row]
+            .columns
+            .ones()
+            .filter(|&column| column != pivot)
+            .max_by_key(|&column| trail.get_decision_level(Lit::from(self.vars[column])));
+        self.set_watch(row, latest);
+
+        let Row { columns, rhs, .. } = &self.rows[row];
+        let parity = columns
+            .ones()
+            .filter(|&column| column != pivot)
+            .fold(*rhs, |parity, column| {
+                parity ^ self.value(trail, column).unwrap()
+            });
+
+        let var = self.vars[pivot];
+        match trail.get_lit_assignment(Lit::from(var)) {
+            None => {
+                let lit = if parity {
+                    Lit::from(var)
+                } else {
+                    -Lit::from(var)
+                };
+                debug!("xor constraints propagate {lit}");
+
+                self.reasons.expand(var, Vec::new());
+                let reason = &mut self.reasons[var];
+                reason.clear();
+                reason.extend(
+                    columns
+                        .ones()
+                        .filter(|&column| column != pivot)
+                        .map(|column| self.vars[column]),
+                );
+                trail.assign_lit(lit, TrailReason::Xor);
+                None
+            }
+            Some(value) if value == parity => None,
+            Some(_) => Some(columns.ones().map(|column| self.vars[column]).collect()),
+        }
+    }
+
+    /// Update all changed rows. Returns the variables of the first falsified row.
+    fn update_changed_rows(&mut self, trail: &mut Trail) -> Option<Vec<Var>> {
+        let mut conflict = None;
+        // Row operations in the middle of a conflict would break the invariants, so all changed rows
+        // are updated.
+        while let Some(row) = self.changed_rows.pop() {
+            let row_conflict = self.update_row(row, trail);
+            conflict = conflict.or(row_conflict);
+        }
+        conflict
+    }
+
+    /// Update the rows watching `column`, whose variable was just assigned.
+    fn column_assigned(&mut self, column: usize, trail: &mut Trail) -> Option<Vec<Var>> {
+        let mut rows = std::mem::take(&mut self.watches[column]);
+        rows.sort_unstable();
+        rows.dedup();
+        rows.retain(|&row| self.watches_column(row, column));
+
+        self.changed_rows.extend_from_slice(&rows);
+        let conflict = self.update_changed_rows(trail);
+
+        // Rows may have moved their watch back to this column while it was updated.
+        rows.retain(|&row| self.watches_column(row, column));
+        rows.append(&mut self.watches[column]);
+        rows.sort_unstable();
+        rows.dedup();
+        self.watches[column] = rows;
+
+        conflict
+    }
+
+    /// Process the assignments on the trail, which weren't processed yet.
+    /// Returns the variables of a falsified row on a conflict.
+    fn propagate(&mut self, trail: &mut Trail) -> Option<Vec<Var>> {
+        if self.outdated && !self.eliminate(trail) {
+            return Some(Vec::new());
+        }
+
+        let mut conflict = self.update_changed_rows(trail);
+        while conflict.is_none() && self.processed_pos < trail.assigned_vars() {
+            let var = trail.trail()[self.processed_pos].lit.var();
+            self.processed_pos += 1;
+            if let Some(column) = self.column(var) {
+                conflict = self.column_assigned(column, trail);
+            }
+        }
+        conflict
+    }
+
+    pub(crate) fn backtrack(&mut self, trail_len: usize) {
+        self.processed_pos = self.processed_pos.min(trail_len);
+    }
+
+    pub(crate) fn is_satisfied(&self, trail: &Trail) -> bool {
+        self.constraints.iter().all(|xor| {
+            xor.vars.iter().fold(false, |parity, &var| {
+                parity ^ trail.is_lit_satisfied(var.into())
+            }) == xor.rhs
+        })
+    }
+}
+
+impl Solver {
+    /// Add the constraint, that an odd number of the literals in `xor` is true.
+    /// A negative literal flips the parity, so `[1, -2]` means, that both variables have the same value.
+    pub fn add_xor<I>(&mut self, xor: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.backtrack(0);
+
+        let mut rhs = true;
+        let mut vars: Vec<Var> = xor
+            .into_iter()
+            .map(Lit::new)
+            .inspect(|lit| rhs ^= lit.is_neg())
+            .map(Lit::var)
+            .collect();
+        vars.sort_by_key(|var| var.get());
+
+        // Variables appearing twice cancel out.
+        let mut deduped: Vec<Var> = Vec::new();
+        for var in vars {
+            if deduped.last() == Some(&var) {
+                deduped.pop();
+            } else {
+                deduped.push(var);
+            }
+        }
+
+        for &var in &deduped {
+            self.trail.expand(var);
+            self.watches.expand(-Lit::from(var), Vec::new());
+        }
+
+        match deduped[..] {
+            [] if rhs => {
+                self.integrate_clause(Vec::new(), None);
+            }
+            [] => {}
+            [var] => {
+                let lit = Lit::from(var);
+                self.integrate_clause(vec![if rhs { lit } else { -lit }], None);
+            }
+            _ => self.xor_db.add(XorConstraint { vars: deduped, rhs }),
+        }
+    }
+
+    /// Propagate the xor constraints using the incrementally eliminated matrix.
+    pub(crate) fn propagate_xors(&mut self) -> ConstraintPropagationResult {
+        if self.xor_db.is_empty() {
+            return ConstraintPropagationResult::Fixpoint;
+        }
+
+        let assigned_vars = self.trail.assigned_vars();
+        if let Some(conflict) = self.xor_db.propagate(&mut self.trail) {
+            let conflict: Vec<Lit> = conflict
+                .into_iter()
+                .map(|var| self.falsified_lit(var))
+                .collect();
+            debug!("xor constraints are falsified {conflict:?}");
+            return self.add_conflict_clause(conflict);
+        }
+
+        let propagated = self.trail.assigned_vars() - assigned_vars;
+        self.stats.propagations += propagated as u64;
+        if propagated == 0 {
+            ConstraintPropagationResult::Fixpoint
+        } else {
+            ConstraintPropagationResult::Changed
+        }
+    }
+
+    /// Reason clause of the literal `lit` propagated by the xor constraints.
+    pub(crate) fn xor_reason(&self, lit: Lit) -> Vec<Lit> {
+        let mut reason = vec![lit];
+        reason.extend(
+            self.xor_db.reasons[lit.var()]
+                .iter()
+                .map(|&var| self.falsified_lit(var)),
+        );
+        reason
+    }
+
+    /// The literal of the assigned variable `var`, which is unsatisfied.
+    fn falsified_lit(&self, var: Var) -> Lit {
+        let lit = Lit::from(var);
+        if self.trail.is_lit_satisfied(lit) {
+            -lit
+        } else {
+            lit
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(columns: &[usize], rhs: bool) -> Row {
+        let mut row = Row::new(4, rhs);
+        for &column in columns {
+            row.columns.set(column);
+        }
+        row
+    }
+
+    #[test]
+    fn elimination() {
+        // x0 ⊕ x1 = 1, x1 ⊕ x2 = 0, x0 ⊕ x2 = 0
+        let mut rows = vec![row(&[0, 1], true), row(&[1, 2], false), row(&[0, 2], false)];
+
+        let rank = gauss_jordan(&mut rows, 4);
+        assert_eq!(rank, 2);
+
+        // The last row is the sum of all constraints, which is contradictory.
+        assert_eq!(rows[2].columns.ones().next(), None);
+        assert!(rows[2].rhs);
+
+        assert_eq!(rows[0].columns.ones().collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(rows[0].pivot, 0);
+        assert_eq!(rows[1].columns.ones().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(rows[1].pivot, 1);
+    }
+
+    #[test]
+    fn xor_propagation() {
+        let mut solver = Solver::new();
+        solver.add_xor([1, 2, 3]);
+        solver.add_xor([2, -3]);
+        solver.add_clause([-2]);
+
+        // x2 = false implies x3 = false and therefore x1 = true.
+        let model = solver.solve().unwrap_sat();
+        assert_eq!(model.as_vec(), vec![1, -2, -3]);
+        assert_eq!(solver.stats().decisions, 0);
+    }
+
+    #[test]
+    fn pivot_moves_to_unassigned_column() {
+        let mut solver = Solver::new();
+        // x1 ⊕ x2 ⊕ x3 = 1 and x3 ⊕ x4 = 1 are eliminated to x1 ⊕ x2 ⊕ x4 = 0 and x3 ⊕ x4 = 1.
+        solver.add_xor([1, 2, 3]);
+        solver.add_xor([3, 4]);
+        assert_eq!(
+            solver.propagate_xors(),
+            ConstraintPropagationResult::Fixpoint
+        );
+        assert_eq!(solver.xor_db.rows[0].pivot, 0);
+
+        // Assigning x1 moves the pivot of the first row to x2.
+        solver.trail.assign_lit(Lit::new(1), TrailReason::Decision);
+        assert_eq!(
+            solver.propagate_xors(),
+            ConstraintPropagationResult::Fixpoint
+        );
+        assert_eq!(solver.xor_db.rows[0].pivot, 1);
+
+        // Then x4 implies both pivots.
+        solver.trail.assign_lit(Lit::new(-4), TrailReason::Decision);
+        assert_eq!(
+            solver.propagate_xors(),
+            ConstraintPropagationResult::Changed
+        );
+        assert_eq!(solver.trail.get_lit_assignment(Lit::new(2)), Some(true));
+        assert_eq!(solver.trail.get_lit_assignment(Lit::new(3)), Some(true));
+        assert_eq!(solver.xor_reason(Lit::new(2)), [2, -1, 4].map(Lit::new));
+
+        // Backtracking keeps the matrix, only the assignments are processed again.
+        solver.backtrack(1);
+        assert_eq!(
+            solver.propagate_xors(),
+            ConstraintPropagationResult::Fixpoint
+        );
+        solver.backtrack(0);
+        assert_eq!(
+            solver.propagate_xors(),
+            ConstraintPropagationResult::Fixpoint
+        );
+        assert!(solver.trail.trail().is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use dissat::{
+    aiger::{Aig, Aiger},
+    bmc::{Bmc, BmcResult, Trace},
+};
+
+/// Two bit counter, which is bad when it reaches 3.
+const COUNTER: &str = "aag 6 0 2 0 4 1
+2 3
+4 11
+12
+6 5 2
+8 4 3
+10 9 7
+12 4 2
+";
+
+/// Latch, which is set once the input is false. The output is the property.
+const TOGGLE: &str = "aag 3 1 1 1 1\n2\n4 6\n4\n6 5 3\n";
+
+/// Simulate the trace and check, that the violated property is reached in the last step.
+fn replay(aig: &Aig, trace: &Trace) -> bool {
+    let mut state = trace.initial.clone();
+    let properties = if aig.bad.is_empty() {
+        &aig.outputs
+    } else {
+        &aig.bad
+    };
+
+    for (step, inputs) in trace.inputs.iter().enumerate() {
+        let mut values = vec![false; aig.max_var as usize + 1];
+        for (&lit, &value) in aig.inputs.iter().zip(inputs) {
+            values[lit as usize / 2] = value;
+        }
+        for (latch, &value) in aig.latches.iter().zip(&state) {
+            values[latch.lit as usize / 2] = value;
+        }
+        let value = |values: &[bool], lit: u32| values[lit as usize / 2] != (lit & 1 == 1);
+        for gate in &aig.ands {
+            values[gate.lhs as usize / 2] = value(&values, gate.rhs0) && value(&values, gate.rhs1);
+        }
+
+        if !aig.constraints.iter().all(|&lit| value(&values, lit)) {
+            return false;
+        }
+        let bad = value(&values, properties[trace.property]);
+        if step + 1 == trace.inputs.len() {
+            return bad;
+        }
+        state = aig
+            .latches
+            .iter()
+            .map(|latch| value(&values, latch.next))
+            .collect();
+    }
+    false
+}
+
+#[test]
+fn counter_reaches_bad_state() {
+    let aig = Aiger::parse(COUNTER.as_bytes()).unwrap();
+
+    let mut bmc = Bmc::new(&aig);
+    assert_eq!(bmc.check(2), BmcResult::NoCounterexample);
+    assert_eq!(bmc.depth(), 3);
+
+    let BmcResult::Counterexample(trace) = bmc.check(10) else {
+        panic!("counter reaches 3");
+    };
+    assert_eq!(trace.inputs.len(), 4);
+    assert_eq!(trace.initial, vec![false, false]);
+    assert!(replay(&aig, &trace));
+    assert_eq!(trace.to_witness(), "1\nb0\n00\n\n\n\n\n.\n");
+}
+
+#[test]
+fn outputs_are_properties_without_bad_states() {
+    let aig = Aiger::parse(TOGGLE.as_bytes()).unwrap();
+
+    let BmcResult::Counterexample(trace) = Bmc::new(&aig).check(5) else {
+        panic!("latch can be set");
+    };
+    assert_eq!(trace.inputs.len(), 2);
+    assert_eq!(trace.inputs[0], vec![false]);
+    assert!(replay(&aig, &trace));
+}
+
+#[test]
+fn constraints_restrict_inputs() {
+    // The input is constrained to be true, so the latch is never set.
+    let aig = Aiger::parse(b"aag 3 1 1 0 1 1 1\n2\n4 6\n4\n2\n6 5 3\n").unwrap();
+    assert_eq!(aig.constraints, vec![2]);
+    assert_eq!(Bmc::new(&aig).check(8), BmcResult::NoCounterexample);
+}
+
+#[test]
+fn uninitialized_latches() {
+    // The latch keeps its unknown initial value, which may be bad immediately.
+    let aig = Aiger::parse(b"aag 1 0 1 0 0 1\n2 2 2\n2\n").unwrap();
+
+    let BmcResult::Counterexample(trace) = Bmc::new(&aig).check(3) else {
+        panic!("initial value is unconstrained");
+    };
+    assert_eq!(trace.initial, vec![true]);
+    assert_eq!(trace.inputs.len(), 1);
+    assert!(replay(&aig, &trace));
+}
+
+#[test]
+fn binary_counter() {
+    let ascii = Aiger::parse(COUNTER.as_bytes()).unwrap();
+
+    // Binary encoding of the same counter, whose and gates are delta encoded.
+    let mut binary = b"aig 6 0 2 0 4 1\n3\n11\n12\n".to_vec();
+    binary.extend([6 - 5, 5 - 2, 8 - 4, 4 - 3, 10 - 9, 9 - 7, 12 - 4, 4 - 2]);
+    let binary = Aiger::parse(&binary).unwrap();
+    assert_eq!(binary, ascii);
+
+    let BmcResult::Counterexample(trace) = Bmc::new(&binary).check(10) else {
+        panic!("counter reaches 3");
+    };
+    assert_eq!(trace.inputs.len(), 4);
+}
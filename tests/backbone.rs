@@ -0,0 +1,69 @@
+mod common;
+
+use common::{brute_force, satisfies, solver, Lcg};
+use dissat::{BackboneResult, Solver};
+
+/// Literals, which are true in all models, or `None` if there is no model.
+fn backbone(clauses: &[Vec<i32>], num_vars: usize) -> Option<Vec<i32>> {
+    let models = brute_force(num_vars, clauses);
+    if models.is_empty() {
+        return None;
+    }
+
+    Some(
+        (1..=num_vars as i32)
+            .flat_map(|var| [var, -var])
+            .filter(|&lit| models.iter().all(|&model| satisfies(model, &[lit])))
+            .collect(),
+    )
+}
+
+#[test]
+fn backbone_of_random_formulas() {
+    let mut rng = Lcg(3);
+    for _ in 0..300 {
+        let num_vars = rng.next(10) + 1;
+        let num_clauses = rng.next(5 * num_vars);
+        let clauses: Vec<Vec<i32>> = (0..num_clauses).map(|_| rng.clause(num_vars)).collect();
+
+        let mut solver = solver(&clauses);
+        while solver.num_vars() < num_vars as usize {
+            solver.new_var();
+        }
+
+        let result = solver.backbone();
+        match backbone(&clauses, num_vars as usize) {
+            Some(backbone) => {
+                assert_eq!(result, BackboneResult::Backbone(backbone.clone()));
+
+                let stats = solver.stats();
+                assert_eq!(stats.backbone_literals, backbone.len() as u64);
+                // Every candidate is either filtered or part of the backbone.
+                assert_eq!(stats.backbone_filtered + stats.backbone_literals, num_vars);
+                assert!(stats.backbone_checks <= num_vars);
+            }
+            None => assert_eq!(result, BackboneResult::Unsat),
+        }
+    }
+}
+
+#[test]
+fn backbone_of_chain() {
+    // 1 and the implication chain 1 -> 2 -> ... -> 20 force all variables, 21 and 22 are free.
+    let mut solver = Solver::new();
+    solver.add_clause([1]);
+    for var in 1..20 {
+        solver.add_clause([-var, var + 1]);
+    }
+    solver.add_clause([21, 22, -20]);
+
+    let expected: Vec<i32> = (1..=20).collect();
+    assert_eq!(solver.backbone(), BackboneResult::Backbone(expected));
+    assert_eq!(solver.stats().backbone_literals, 20);
+    // The forced literals are fixed without tests.
+    assert!((1..=2).contains(&solver.stats().backbone_checks));
+
+    // The formula is unchanged, 21 and 22 are still free.
+    assert!(solver.solve_with_assumptions(&[-21, 22]).is_sat());
+    assert!(solver.solve_with_assumptions(&[21, -22]).is_sat());
+}
@@ -0,0 +1,135 @@
+use dissat::{
+    bitvec::BitVec,
+    circuit::{Circuit, Signal},
+    Solver,
+};
+
+/// Bits needed to represent `value`.
+fn bits(value: u64) -> usize {
+    64 - value.leading_zeros() as usize
+}
+
+/// Solver and circuit for `x * y = n` with `1 < x <= y`.
+fn factoring(n: u64) -> (Solver, Circuit, BitVec, BitVec) {
+    let mut solver = Solver::new();
+    let mut circuit = Circuit::new();
+    let width = bits(n);
+    let x = BitVec::new_input(&mut circuit, &mut solver, width.div_ceil(2));
+    let y = BitVec::new_input(&mut circuit, &mut solver, width);
+
+    let product = circuit.bv_mul(&x, &y);
+    let is_product = circuit.bv_eq(&product, &BitVec::constant(n, width));
+    let one = BitVec::constant(1, 1);
+    let nontrivial = circuit.bv_ugt(&x, &one);
+    let ordered = circuit.bv_ule(&x, &y);
+
+    for signal in [is_product, nontrivial, ordered] {
+        circuit.assert(&mut solver, signal);
+    }
+    (solver, circuit, x, y)
+}
+
+#[test]
+fn prime_examples() {
+    // The prime*.cnf examples ask for nontrivial factors of squares of primes.
+    for (n, factor) in [(25, 5), (49, 7), (121, 11), (169, 13), (289, 17), (361, 19)] {
+        let (mut solver, circuit, x, y) = factoring(n);
+        let model = solver.solve().unwrap_sat();
+        assert_eq!(x.value(&circuit, &model), factor);
+        assert_eq!(y.value(&circuit, &model), factor);
+    }
+
+    for prime in [13, 97, 251] {
+        let (mut solver, ..) = factoring(prime);
+        assert!(solver.solve().is_unsat());
+    }
+}
+
+#[test]
+fn factor_example() {
+    // Like the factor*.cnf examples, but with a smaller semiprime: 10403 = 101 * 103.
+    let (mut solver, mut circuit, x, y) = factoring(10403);
+    let model = solver.solve().unwrap_sat();
+    assert_eq!(x.value(&circuit, &model), 101);
+    assert_eq!(y.value(&circuit, &model), 103);
+
+    // There is no other factorization.
+    let other = circuit.bv_eq(&x, &BitVec::constant(101, 7));
+    circuit.assert(&mut solver, !other);
+    assert!(solver.solve().is_unsat());
+}
+
+#[test]
+fn sqrt_examples() {
+    for root in [53, 59, 61, 67, 71, 97, 101, 103, 127, 251] {
+        let n = root * root;
+
+        let mut solver = Solver::new();
+        let mut circuit = Circuit::new();
+        let x = BitVec::new_input(&mut circuit, &mut solver, bits(n).div_ceil(2));
+        let square = circuit.bv_mul(&x, &x);
+        let is_square = circuit.bv_eq(&square, &BitVec::constant(n, bits(n)));
+        circuit.assert(&mut solver, is_square);
+
+        let model = solver.solve().unwrap_sat();
+        assert_eq!(x.value(&circuit, &model), root, "sqrt{n}.cnf");
+    }
+}
+
+/// Fix the input bits to `value` and return the value of `result`.
+fn evaluate(
+    solver: &mut Solver,
+    circuit: &mut Circuit,
+    inputs: &[(&BitVec, u64)],
+    result: &BitVec,
+) -> u64 {
+    let mut assumptions = Vec::new();
+    for (input, value) in inputs {
+        for (idx, &bit) in input.bits().iter().enumerate() {
+            let lit = circuit.encode(solver, bit);
+            assumptions.push(if value >> idx & 1 == 1 { lit } else { -lit });
+        }
+    }
+    for &bit in result.bits() {
+        circuit.encode(solver, bit);
+    }
+
+    let model = solver.solve_with_assumptions(&assumptions).unwrap_sat();
+    result.value(circuit, &model)
+}
+
+#[test]
+fn operations_on_all_inputs() {
+    let mut solver = Solver::new();
+    let mut circuit = Circuit::new();
+    let a = BitVec::new_input(&mut circuit, &mut solver, 4);
+    let b = BitVec::new_input(&mut circuit, &mut solver, 3);
+
+    let flag = |signal: Signal| BitVec::from_signals(vec![signal]);
+    let sum = circuit.bv_add(&a, &b);
+    let product = circuit.bv_mul(&a, &b);
+    let less = flag(circuit.bv_ult(&a, &b));
+    let less_eq = flag(circuit.bv_ule(&a, &b));
+    let equal = flag(circuit.bv_eq(&a, &b));
+    let shl = circuit.bv_shl(&a, 1);
+    let lshr = circuit.bv_lshr(&a, 2);
+    let shl_by = circuit.bv_shl_by(&a, &b);
+    let lshr_by = circuit.bv_lshr_by(&a, &b);
+
+    for x in 0..16 {
+        for y in 0..8 {
+            let inputs = [(&a, x), (&b, y)];
+            let mut eval = |result: &BitVec| evaluate(&mut solver, &mut circuit, &inputs, result);
+
+            assert_eq!(eval(&sum), x + y);
+            assert_eq!(eval(&product), x * y);
+            assert_eq!(eval(&less), (x < y) as u64);
+            assert_eq!(eval(&less_eq), (x <= y) as u64);
+            assert_eq!(eval(&equal), (x == y) as u64);
+            assert_eq!(eval(&shl), x << 1 & 15);
+            assert_eq!(eval(&lshr), x >> 2);
+            assert_eq!(eval(&shl_by), x.checked_shl(y as u32).unwrap_or(0) & 15);
+            assert_eq!(eval(&lshr_by), x.checked_shr(y as u32).unwrap_or(0));
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::{Duration, Instant};
+
+use dissat::{Budget, Solver};
+
+fn pigeonhole() -> Solver {
+    let input = std::fs::read_to_string("cnf_examples/ph6.cnf").unwrap();
+    Solver::from_dimacs(&input).unwrap()
+}
+
+#[test]
+fn conflict_limit() {
+    let mut solver = pigeonhole();
+    solver.set_budget(Budget {
+        max_conflicts: Some(10),
+        ..Default::default()
+    });
+
+    assert!(solver.solve().is_unknown());
+    assert_eq!(solver.stats().contradictions, 10);
+
+    // Every call gets the full budget again.
+    assert!(solver.solve().is_unknown());
+    assert_eq!(solver.stats().contradictions, 20);
+
+    solver.set_budget(Budget::default());
+    assert!(solver.solve().is_unsat());
+}
+
+#[test]
+fn decision_and_propagation_limits() {
+    let mut solver = pigeonhole();
+    solver.set_budget(Budget {
+        max_decisions: Some(5),
+        ..Default::default()
+    });
+    assert!(solver.solve().is_unknown());
+    assert_eq!(solver.stats().decisions, 5);
+
+    let mut solver = pigeonhole();
+    solver.set_budget(Budget {
+        max_propagations: Some(50),
+        ..Default::default()
+    });
+    assert!(solver.solve().is_unknown());
+    assert!(solver.stats().propagations >= 50);
+}
+
+#[test]
+fn deadline() {
+    let mut solver = pigeonhole();
+    solver.set_budget(Budget {
+        deadline: Some(Instant::now()),
+        ..Default::default()
+    });
+    assert!(solver.solve().is_unknown());
+
+    solver.set_budget(Budget {
+        deadline: Some(Instant::now() + Duration::from_secs(600)),
+        ..Default::default()
+    });
+    assert!(solver.solve().is_unsat());
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::{hole_vars, pigeon_vars, satisfies, Lcg};
+use dissat::Solver;
+
+fn count_satisfied(lits: &[i32], assignment: u32) -> usize {
+    let mut lits = lits.to_vec();
+    lits.sort();
+    lits.dedup();
+    lits.iter()
+        .filter(|&&lit| satisfies(assignment, &[lit]))
+        .count()
+}
+
+/// Pigeonhole problem, expressed with native cardinality constraints instead of clauses.
+fn native_pigeonhole(pigeons: i32, holes: i32) -> Solver {
+    let mut solver = Solver::new();
+    for pigeon in pigeon_vars(pigeons, holes) {
+        solver.add_at_least_k(&pigeon, 1);
+    }
+    for hole in hole_vars(pigeons, holes) {
+        solver.add_at_most_k(&hole, 1);
+    }
+
+    solver
+}
+
+#[test]
+fn pigeonhole_native() {
+    assert!(native_pigeonhole(7, 6).solve().is_unsat());
+    assert!(native_pigeonhole(8, 7).solve().is_unsat());
+    assert!(native_pigeonhole(7, 7).solve().is_sat());
+}
+
+#[test]
+fn random_cardinality_constraints() {
+    let mut rng = Lcg(7);
+    let num_vars = 10;
+
+    for _ in 0..300 {
+        let at_most: Vec<(Vec<i32>, usize)> = (0..rng.next(4) + 1)
+            .map(|_| {
+                let len = rng.next(7) + 1;
+                (rng.lits(len, num_vars), rng.next(len) as usize)
+            })
+            .collect();
+        let at_least: Vec<(Vec<i32>, usize)> = (0..rng.next(3))
+            .map(|_| {
+                let len = rng.next(7) + 1;
+                (rng.lits(len, num_vars), rng.next(len + 1) as usize)
+            })
+            .collect();
+        let clauses: Vec<Vec<i32>> = (0..rng.next(15)).map(|_| rng.lits(3, num_vars)).collect();
+
+        let mut solver = Solver::new();
+        for (lits, k) in &at_most {
+            solver.add_at_most_k(lits, *k);
+        }
+        for (lits, k) in &at_least {
+            solver.add_at_least_k(lits, *k);
+        }
+        for clause in &clauses {
+            solver.add_clause(clause.iter().copied());
+        }
+
+        let satisfiable = (0..1 << num_vars).any(|assignment| {
+            at_most
+                .iter()
+                .all(|(lits, k)| count_satisfied(lits, assignment) <= *k)
+                && at_least
+                    .iter()
+                    .all(|(lits, k)| count_satisfied(lits, assignment) >= *k)
+                && clauses.iter().all(|clause| satisfies(assignment, clause))
+        });
+
+        let result = solver.solve();
+        assert_eq!(
+            result.is_sat(),
+            satisfiable,
+            "{at_most:?} {at_least:?} {clauses:?}"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use common::Lcg;
+use dissat::{
+    circuit::{Circuit, GateEncoding, Signal},
+    Solver,
+};
+
+const ENCODINGS: [GateEncoding; 2] = [GateEncoding::Tseitin, GateEncoding::PlaistedGreenbaum];
+
+fn random_signal(rng: &mut Lcg, signals: &[Signal]) -> Signal {
+    let signal = signals[rng.next(signals.len() as u64) as usize];
+    if rng.next(2) == 0 {
+        signal
+    } else {
+        !signal
+    }
+}
+
+fn random_circuit(rng: &mut Lcg, circuit: &mut Circuit, num_inputs: i32) -> Signal {
+    let mut signals: Vec<Signal> = (1..=num_inputs).map(|var| circuit.lit(var)).collect();
+    signals.push(Signal::TRUE);
+
+    for _ in 0..rng.next(12) + 1 {
+        let (a, b, c) = (
+            random_signal(rng, &signals),
+            random_signal(rng, &signals),
+            random_signal(rng, &signals),
+        );
+        let gate = match rng.next(5) {
+            0 => circuit.and(a, b),
+            1 => circuit.or(a, b),
+            2 => circuit.xor(a, b),
+            3 => circuit.eq(a, b),
+            _ => circuit.ite(a, b, c),
+        };
+        signals.push(gate);
+    }
+
+    *signals.last().unwrap()
+}
+
+fn inputs(num_inputs: i32, assignment: u32) -> Vec<i32> {
+    (1..=num_inputs)
+        .map(|var| {
+            if assignment >> (var - 1) & 1 == 1 {
+                var
+            } else {
+                -var
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn random_circuits() {
+    let mut rng = Lcg(11);
+    let num_inputs = 5;
+
+    for _ in 0..200 {
+        for encoding in ENCODINGS {
+            let mut circuit = Circuit::with_encoding(encoding);
+            let output = random_circuit(&mut rng, &mut circuit, num_inputs);
+
+            let mut solver = Solver::new();
+            while solver.num_vars() < num_inputs as usize {
+                solver.new_var();
+            }
+            let lit = circuit.encode(&mut solver, output);
+
+            for assignment in 0..1 << num_inputs {
+                let mut assumptions = inputs(num_inputs, assignment);
+                assumptions.push(lit);
+                let model = solver.solve_with_assumptions(&assumptions);
+
+                let mut assumptions = inputs(num_inputs, assignment);
+                assumptions.push(-lit);
+                let counter_model = solver.solve_with_assumptions(&assumptions);
+
+                // Exactly one polarity is consistent with the inputs.
+                assert_ne!(model.is_sat(), counter_model.is_sat());
+                if let dissat::Result::Sat(model) = model {
+                    assert!(circuit.value(output, &model));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn asserted_circuits() {
+    let mut rng = Lcg(5);
+    let num_inputs = 4;
+
+    for _ in 0..200 {
+        for encoding in ENCODINGS {
+            let mut circuit = Circuit::with_encoding(encoding);
+            let output = random_circuit(&mut rng, &mut circuit, num_inputs);
+
+            let mut solver = Solver::new();
+            while solver.num_vars() < num_inputs as usize {
+                solver.new_var();
+            }
+            circuit.assert(&mut solver, output);
+
+            for assignment in 0..1 << num_inputs {
+                let expected = {
+                    let mut check = Solver::new();
+                    for lit in inputs(num_inputs, assignment) {
+                        check.add_clause([lit]);
+                    }
+                    circuit.value(output, &check.solve().unwrap_sat())
+                };
+
+                let result = solver.solve_with_assumptions(&inputs(num_inputs, assignment));
+                assert_eq!(result.is_sat(), expected);
+            }
+        }
+    }
+}
+
+/// Ripple carry adder of two little endian numbers.
+fn add(circuit: &mut Circuit, x: &[Signal], y: &[Signal]) -> Vec<Signal> {
+    let mut carry = Signal::FALSE;
+    let mut sum = Vec::new();
+    for (&a, &b) in x.iter().zip(y) {
+        let half = circuit.xor(a, b);
+        sum.push(circuit.xor(half, carry));
+
+        let both = circuit.and(a, b);
+        let propagated = circuit.and(half, carry);
+        carry = circuit.or(both, propagated);
+    }
+    sum.push(carry);
+    sum
+}
+
+#[test]
+fn adder_is_commutative() {
+    // Miter of x + y and y + x like the add*.cnf examples, which is unsatisfiable.
+    for encoding in ENCODINGS {
+        for width in [4, 8, 16] {
+            let mut solver = Solver::new();
+            let mut circuit = Circuit::with_encoding(encoding);
+            let x: Vec<Signal> = (0..width).map(|_| circuit.new_input(&mut solver)).collect();
+            let y: Vec<Signal> = (0..width).map(|_| circuit.new_input(&mut solver)).collect();
+
+            let first = add(&mut circuit, &x, &y);
+            let second = add(&mut circuit, &y, &x);
+            let differences: Vec<Signal> = first
+                .iter()
+                .zip(&second)
+                .map(|(&a, &b)| circuit.xor(a, b))
+                .collect();
+            let differ = circuit.or_all(differences);
+
+            circuit.assert(&mut solver, differ);
+            assert!(solver.solve().is_unsat());
+        }
+    }
+}
+
+#[test]
+fn adder_computes_sum() {
+    let mut solver = Solver::new();
+    let mut circuit = Circuit::new();
+    let x: Vec<Signal> = (0..6).map(|_| circuit.new_input(&mut solver)).collect();
+    let y: Vec<Signal> = (0..6).map(|_| circuit.new_input(&mut solver)).collect();
+    let sum = add(&mut circuit, &x, &y);
+
+    // x + y = 100 with x = 37.
+    for (bit, &signal) in sum.iter().enumerate() {
+        let signal = if 100 >> bit & 1 == 1 { signal } else { !signal };
+        circuit.assert(&mut solver, signal);
+    }
+    for (bit, &signal) in x.iter().enumerate() {
+        let signal = if 37 >> bit & 1 == 1 { signal } else { !signal };
+        circuit.assert(&mut solver, signal);
+    }
+
+    let model = solver.solve().unwrap_sat();
+    let y = y
+        .iter()
+        .enumerate()
+        .filter(|(_, &signal)| circuit.value(signal, &model))
+        .fold(0, |y, (bit, _)| y | 1 << bit);
+    assert_eq!(y, 63);
+}
@@ -130,6 +130,9 @@ fn example(input: &Path) {
                 panic!("Result is UNSAT, but expected SAT")
             }
             (dissat::Result::Unsat(_), ExpectedSolution::Unsat) => {}
+            (dissat::Result::Unknown, _) => {
+                panic!("Result is UNKNOWN, but no budget was set")
+            }
         }
     }
 
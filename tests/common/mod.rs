@@ -0,0 +1,165 @@
+//! Helpers shared by the integration tests. Every test crate only uses some of them.
+#![allow(dead_code)]
+
+use std::{collections::HashSet, path::Path};
+
+use dissat::{Config, Dimacs, Solver};
+
+/// Simple deterministic pseudo random numbers, so the instances are reproducible.
+pub struct Lcg(pub u64);
+
+impl Lcg {
+    pub fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+
+    /// `len` random literals of the variables `1..=num_vars`.
+    pub fn lits(&mut self, len: u64, num_vars: u64) -> Vec<i32> {
+        (0..len)
+            .map(|_| {
+                let var = self.next(num_vars) as i32 + 1;
+                if self.next(2) == 0 {
+                    var
+                } else {
+                    -var
+                }
+            })
+            .collect()
+    }
+
+    /// Clause of one to three random literals of the variables `1..=num_vars`.
+    pub fn clause(&mut self, num_vars: u64) -> Vec<i32> {
+        let len = self.next(3) + 1;
+        self.lits(len, num_vars)
+    }
+}
+
+/// Content of the example `name` in `cnf_examples`.
+pub fn read(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("cnf_examples")
+        .join(name);
+    std::fs::read_to_string(path).unwrap()
+}
+
+/// Clauses of the example `name` in `cnf_examples`.
+pub fn read_clauses(name: &str) -> Vec<Vec<i32>> {
+    Dimacs::parse(&read(name)).unwrap()
+}
+
+/// Whether `clause` is satisfied by `assignment`, in which bit `i` is the value of variable `i + 1`.
+pub fn satisfies(assignment: u32, clause: &[i32]) -> bool {
+    clause
+        .iter()
+        .any(|&lit| (assignment >> (lit.unsigned_abs() - 1) & 1 == 1) == (lit > 0))
+}
+
+/// All assignments of the variables `1..=num_vars` satisfying `clauses`, found by brute force.
+pub fn brute_force(num_vars: usize, clauses: &[Vec<i32>]) -> Vec<u32> {
+    (0..1u32 << num_vars)
+        .filter(|&assignment| clauses.iter().all(|clause| satisfies(assignment, clause)))
+        .collect()
+}
+
+/// Whether some assignment of the variables `1..=num_vars` satisfies `clauses` and `assumptions`.
+pub fn brute_force_sat(num_vars: usize, clauses: &[Vec<i32>], assumptions: &[i32]) -> bool {
+    (0..1u32 << num_vars).any(|assignment| {
+        assumptions.iter().all(|&lit| satisfies(assignment, &[lit]))
+            && clauses.iter().all(|clause| satisfies(assignment, clause))
+    })
+}
+
+/// Solve random formulas under random assumptions with a solver for each of `configs` and check
+/// the results against brute force.
+pub fn assert_configs_match_brute_force(seed: u64, rounds: usize, configs: &[Config]) {
+    let mut rng = Lcg(seed);
+    for _ in 0..rounds {
+        let num_vars = rng.next(12) + 1;
+        let num_clauses = rng.next(6 * num_vars);
+        let clauses: Vec<Vec<i32>> = (0..num_clauses).map(|_| rng.lits(3, num_vars)).collect();
+        let num_assumptions = rng.next(3);
+        let assumptions = rng.lits(num_assumptions, num_vars);
+        let expected = brute_force_sat(num_vars as usize, &clauses, &assumptions);
+
+        for config in configs {
+            let mut solver = Solver::with_config(config.clone());
+            while solver.num_vars() < num_vars as usize {
+                solver.new_var();
+            }
+            for clause in &clauses {
+                solver.add_clause(clause.iter().copied());
+            }
+
+            match solver.solve_with_assumptions(&assumptions) {
+                dissat::Result::Sat(model) => {
+                    assert!(expected, "{config:?}");
+                    assert!(assumptions.iter().all(|&lit| model.lit(lit)));
+                    assert!(clauses
+                        .iter()
+                        .all(|clause| clause.iter().any(|&lit| model.lit(lit))));
+                }
+                dissat::Result::Unsat(_) => assert!(!expected, "{config:?}"),
+                dissat::Result::Unknown => unreachable!(),
+            }
+        }
+    }
+}
+
+/// Number of distinct assignments of `projection`, which can be extended to a model.
+pub fn projected_count(num_vars: usize, clauses: &[Vec<i32>], projection: &[i32]) -> usize {
+    brute_force(num_vars, clauses)
+        .into_iter()
+        .map(|assignment| {
+            projection
+                .iter()
+                .map(|&var| satisfies(assignment, &[var]))
+                .collect::<Vec<bool>>()
+        })
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Solver for `clauses`.
+pub fn solver(clauses: &[Vec<i32>]) -> Solver {
+    let mut solver = Solver::new();
+    for clause in clauses {
+        solver.add_clause(clause.iter().copied());
+    }
+    solver
+}
+
+/// For each pigeon, the variables placing it in one of the holes.
+pub fn pigeon_vars(pigeons: i32, holes: i32) -> Vec<Vec<i32>> {
+    (0..pigeons)
+        .map(|pigeon| (0..holes).map(|hole| pigeon * holes + hole + 1).collect())
+        .collect()
+}
+
+/// For each hole, the variables placing one of the pigeons in it.
+pub fn hole_vars(pigeons: i32, holes: i32) -> Vec<Vec<i32>> {
+    (0..holes)
+        .map(|hole| {
+            (0..pigeons)
+                .map(|pigeon| pigeon * holes + hole + 1)
+                .collect()
+        })
+        .collect()
+}
+
+/// Pigeonhole formula, where every pigeon sits in a hole and no two pigeons share a hole. With more
+/// pigeons than holes it is unsatisfiable and hard for resolution based solvers.
+pub fn pigeonhole(pigeons: i32, holes: i32) -> Vec<Vec<i32>> {
+    let mut clauses = pigeon_vars(pigeons, holes);
+    for hole in hole_vars(pigeons, holes) {
+        for (idx, &first) in hole.iter().enumerate() {
+            for &second in &hole[idx + 1..] {
+                clauses.push(vec![-first, -second]);
+            }
+        }
+    }
+    clauses
+}
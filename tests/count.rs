@@ -0,0 +1,98 @@
+mod common;
+
+use common::{projected_count, read, solver, Lcg};
+use dissat::{BigUint, Dimacs, Solver};
+
+const EXAMPLES: [&str; 24] = [
+    "block0.cnf",
+    "elimclash.cnf",
+    "elimredundant.cnf",
+    "empty.cnf",
+    "false.cnf",
+    "full3.cnf",
+    "full7.cnf",
+    "ph2.cnf",
+    "ph3.cnf",
+    "regr000.cnf",
+    "sat0.cnf",
+    "sat1.cnf",
+    "sat5.cnf",
+    "sat6.cnf",
+    "sat10.cnf",
+    "sat13.cnf",
+    "sub0.cnf",
+    "unit0.cnf",
+    "unit1.cnf",
+    "unit2.cnf",
+    "unit4.cnf",
+    "unit5.cnf",
+    "unit6.cnf",
+    "unit7.cnf",
+];
+
+#[test]
+fn count_examples() {
+    for name in EXAMPLES {
+        let input = read(name);
+        let clauses = Dimacs::parse(&input).unwrap();
+        let mut solver = Solver::from_dimacs(&input).unwrap();
+        let num_vars = solver.num_vars();
+        let all: Vec<i32> = (1..=num_vars as i32).collect();
+        let half: Vec<i32> = (1..=num_vars as i32 / 2).collect();
+
+        assert_eq!(
+            solver.count_models().unwrap().to_u64(),
+            Some(projected_count(num_vars, &clauses, &all) as u64),
+            "{name}"
+        );
+        assert_eq!(
+            solver.count_projected_models(&half).unwrap().to_u64(),
+            Some(projected_count(num_vars, &clauses, &half) as u64),
+            "{name} projected"
+        );
+    }
+}
+
+#[test]
+fn count_random_formulas() {
+    let mut rng = Lcg(42);
+    for _ in 0..200 {
+        let num_vars = rng.next(12) + 1;
+        let num_clauses = rng.next(4 * num_vars);
+        let clauses: Vec<Vec<i32>> = (0..num_clauses).map(|_| rng.lits(3, num_vars)).collect();
+        let projection: Vec<i32> = (1..=num_vars as i32).filter(|_| rng.next(2) == 0).collect();
+
+        let mut solver = solver(&clauses);
+        while solver.num_vars() < num_vars as usize {
+            solver.new_var();
+        }
+
+        let all: Vec<i32> = (1..=num_vars as i32).collect();
+        let expected = projected_count(num_vars as usize, &clauses, &all) as u64;
+        assert_eq!(solver.count_models().unwrap().to_u64(), Some(expected));
+        assert_eq!(
+            solver.count_projected_models(&projection).unwrap().to_u64(),
+            Some(projected_count(num_vars as usize, &clauses, &projection) as u64)
+        );
+
+        // Counting leaves the solver usable.
+        assert_eq!(solver.solve().is_sat(), expected > 0);
+    }
+}
+
+#[test]
+fn count_exceeds_u64() {
+    // 80 independent clauses `a ∨ b` have 3^80 models.
+    let mut solver = Solver::new();
+    for idx in 0..80 {
+        solver.add_clause([2 * idx + 1, 2 * idx + 2]);
+    }
+    let expected = (0..80).fold(BigUint::one(), |acc, _| &acc * &BigUint::from(3));
+    assert_eq!(solver.count_models(), Some(expected));
+
+    // Variables of the projection, which don't occur in the formula, double the count.
+    let projection: Vec<i32> = (1..=200).step_by(2).collect();
+    let expected = (0..80).fold(BigUint::one() << 20, |acc, _| &acc * &BigUint::from(2));
+    assert_eq!(solver.count_projected_models(&projection), Some(expected));
+    assert_eq!(solver.num_vars(), 199);
+}
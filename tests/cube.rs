@@ -0,0 +1,69 @@
+mod common;
+
+use common::{brute_force, read, satisfies, solver, Lcg};
+use dissat::Solver;
+
+#[test]
+fn cubes_partition_models() {
+    let mut rng = Lcg(11);
+    for _ in 0..200 {
+        let num_vars = rng.next(12) + 1;
+        let num_clauses = rng.next(5 * num_vars);
+        let clauses: Vec<Vec<i32>> = (0..num_clauses).map(|_| rng.lits(3, num_vars)).collect();
+        let depth = rng.next(5) as usize;
+
+        let mut solver = solver(&clauses);
+        while solver.num_vars() < num_vars as usize {
+            solver.new_var();
+        }
+
+        let cubes = solver.cubes(depth).unwrap();
+        assert!(cubes.len() <= 1 << depth);
+
+        let models = brute_force(num_vars as usize, &clauses);
+        for &model in &models {
+            let containing = cubes
+                .iter()
+                .filter(|cube| cube.iter().all(|&lit| satisfies(model, &[lit])))
+                .count();
+            assert_eq!(containing, 1);
+        }
+
+        let result = solver.conquer(&cubes);
+        assert_eq!(result.is_sat(), !models.is_empty());
+        if let dissat::Result::Sat(model) = result {
+            assert!(clauses
+                .iter()
+                .all(|clause| clause.iter().any(|&lit| model.lit(lit))));
+        }
+    }
+}
+
+#[test]
+fn conquer_examples() {
+    for (name, sat) in [
+        ("ph4.cnf", false),
+        ("ph5.cnf", false),
+        ("full5.cnf", false),
+        ("prime169.cnf", true),
+        ("prime961.cnf", true),
+        ("sat13.cnf", true),
+    ] {
+        let mut solver = Solver::from_dimacs(&read(name)).unwrap();
+        let cubes = solver.cubes(4).unwrap();
+        assert!(cubes.len() <= 16);
+        assert_eq!(solver.conquer(&cubes).is_sat(), sat, "{name}");
+    }
+}
+
+#[test]
+fn unsat_formula_without_cubes() {
+    // Both polarities of 1 fail by propagation.
+    let mut solver = Solver::new();
+    solver.add_clause([1, 2]);
+    solver.add_clause([1, -2]);
+    solver.add_clause([-1, 3]);
+    solver.add_clause([-1, -3]);
+    assert_eq!(solver.cubes(3), Some(Vec::new()));
+    assert!(solver.conquer(&[]).is_unsat());
+}
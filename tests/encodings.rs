@@ -0,0 +1,143 @@
+use dissat::{
+    encodings::{self, AtMostKEncoding, AtMostOneEncoding, Cnf, VarAllocator},
+    Solver,
+};
+
+const AT_MOST_ONE: [AtMostOneEncoding; 4] = [
+    AtMostOneEncoding::Pairwise,
+    AtMostOneEncoding::SequentialCounter,
+    AtMostOneEncoding::Commander,
+    AtMostOneEncoding::Product,
+];
+
+const AT_MOST_K: [AtMostKEncoding; 4] = [
+    AtMostKEncoding::SequentialCounter,
+    AtMostKEncoding::Totalizer,
+    AtMostKEncoding::ModuloTotalizer,
+    AtMostKEncoding::CardinalityNetwork,
+];
+
+/// Literals over the variables `1..=n` with alternating polarity.
+fn lits(n: usize) -> Vec<i32> {
+    (1..=n as i32)
+        .map(|var| if var % 3 == 0 { -var } else { var })
+        .collect()
+}
+
+/// Assumptions fixing the literals to the bits of `assignment`.
+fn assumptions(lits: &[i32], assignment: u32) -> Vec<i32> {
+    lits.iter()
+        .enumerate()
+        .map(|(idx, &lit)| {
+            if assignment >> idx & 1 == 1 {
+                lit
+            } else {
+                -lit
+            }
+        })
+        .collect()
+}
+
+/// Check, that the encoding is satisfiable under every assignment of the literals,
+/// iff `accepts` holds for the number of true literals.
+fn check(n: usize, encode: impl Fn(&mut Solver, &[i32]), accepts: impl Fn(usize) -> bool) {
+    let lits = lits(n);
+
+    let mut solver = Solver::new();
+    while solver.num_vars() < n {
+        solver.new_var();
+    }
+    encode(&mut solver, &lits);
+
+    for assignment in 0..1u32 << n {
+        let result = solver.solve_with_assumptions(&assumptions(&lits, assignment));
+        assert_eq!(
+            result.is_sat(),
+            accepts(assignment.count_ones() as usize),
+            "{n} literals, assignment {assignment:b}"
+        );
+    }
+}
+
+#[test]
+fn at_most_one() {
+    for encoding in AT_MOST_ONE {
+        for n in 0..=10 {
+            check(
+                n,
+                |solver, lits| encodings::at_most_one(solver, lits, encoding),
+                |count| count <= 1,
+            );
+        }
+    }
+}
+
+#[test]
+fn exactly_one() {
+    for encoding in AT_MOST_ONE {
+        for n in 1..=8 {
+            check(
+                n,
+                |solver, lits| encodings::exactly_one(solver, lits, encoding),
+                |count| count == 1,
+            );
+        }
+    }
+}
+
+#[test]
+fn at_most_k() {
+    for encoding in AT_MOST_K {
+        for n in 0..=9 {
+            for k in 0..=n {
+                check(
+                    n,
+                    |solver, lits| encodings::at_most_k(solver, lits, k, encoding),
+                    |count| count <= k,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn at_least_and_exactly_k() {
+    for encoding in AT_MOST_K {
+        for n in 1..=7 {
+            for k in 0..=n + 1 {
+                check(
+                    n,
+                    |solver, lits| encodings::at_least_k(solver, lits, k, encoding),
+                    |count| count >= k,
+                );
+                check(
+                    n,
+                    |solver, lits| encodings::exactly_k(solver, lits, k, encoding),
+                    |count| count == k,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn cnf_reserves_auxiliary_variables() {
+    let mut solver = Solver::new();
+    solver.add_clause([1, 2, 3, 4, 5]);
+
+    let mut cnf = Cnf::new(VarAllocator::for_solver(&solver));
+    encodings::at_most_k(&mut cnf, &[1, 2, 3, 4, 5], 2, AtMostKEncoding::Totalizer);
+    assert!(cnf.vars.num_vars() > 5);
+
+    cnf.add_to(&mut solver);
+    assert_eq!(solver.num_vars(), cnf.vars.num_vars());
+
+    // Variables allocated by the solver don't clash with the auxiliary variables.
+    let var = solver.new_var();
+    assert_eq!(var as usize, cnf.vars.num_vars() + 1);
+    solver.add_clause([var]);
+
+    let model = solver.solve().unwrap_sat();
+    assert!((1..=5).filter(|&var| model.lit(var)).count() <= 2);
+    assert!(model.lit(var));
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use std::collections::HashSet;
+
+use common::{projected_count, read};
+use dissat::{Blocking, Dimacs, Model, Solver};
+
+const BLOCKING: [Blocking; 2] = [Blocking::Clauses, Blocking::Decisions];
+
+fn projected(model: &Model, projection: &[i32]) -> Vec<bool> {
+    projection.iter().map(|&var| model.lit(var)).collect()
+}
+
+#[test]
+fn full_formulas_have_no_models() {
+    for n in 1..=7 {
+        for blocking in BLOCKING {
+            let mut solver = Solver::from_dimacs(&read(&format!("full{n}.cnf"))).unwrap();
+            let mut models = solver.models().blocking(blocking);
+            assert_eq!(models.by_ref().count(), 0);
+            assert!(models.is_exhausted());
+        }
+    }
+}
+
+#[test]
+fn exact_counts_of_small_examples() {
+    let examples = [
+        "block0.cnf",
+        "elimclash.cnf",
+        "elimredundant.cnf",
+        "empty.cnf",
+        "false.cnf",
+        "ph2.cnf",
+        "ph3.cnf",
+        "regr000.cnf",
+        "sat0.cnf",
+        "sat1.cnf",
+        "sat5.cnf",
+        "sat13.cnf",
+        "sub0.cnf",
+        "unit5.cnf",
+        "unit7.cnf",
+    ];
+
+    for name in examples {
+        let input = read(name);
+        let clauses = Dimacs::parse(&input).unwrap();
+
+        for blocking in BLOCKING {
+            let mut solver = Solver::from_dimacs(&input).unwrap();
+            let num_vars = solver.num_vars();
+            let all: Vec<i32> = (1..=num_vars as i32).collect();
+            let expected = projected_count(num_vars, &clauses, &all);
+
+            let models: Vec<Model> = solver.models().blocking(blocking).collect();
+            assert_eq!(models.len(), expected, "{name} {blocking:?}");
+            let distinct: HashSet<_> = models.iter().map(|m| projected(m, &all)).collect();
+            assert_eq!(distinct.len(), expected, "{name} {blocking:?}");
+            for model in &models {
+                assert!(clauses
+                    .iter()
+                    .all(|clause| clause.iter().any(|&lit| model.lit(lit))));
+            }
+        }
+    }
+}
+
+#[test]
+fn projected_counts() {
+    let input = read("unit7.cnf");
+    let clauses = Dimacs::parse(&input).unwrap();
+
+    for blocking in BLOCKING {
+        for projection in [vec![], vec![1], vec![2, 4], vec![1, 3, 4]] {
+            let mut solver = Solver::from_dimacs(&input).unwrap();
+            let num_vars = solver.num_vars();
+            let expected = projected_count(num_vars, &clauses, &projection);
+
+            let models: HashSet<Vec<bool>> = solver
+                .models()
+                .project(&projection)
+                .blocking(blocking)
+                .map(|model| projected(&model, &projection))
+                .collect();
+            assert_eq!(models.len(), expected, "{projection:?} {blocking:?}");
+        }
+    }
+}
+
+#[test]
+fn limit() {
+    for blocking in BLOCKING {
+        // 2^5 models without any clauses.
+        let mut solver = Solver::new();
+        solver.add_clause([1, -1]);
+        while solver.num_vars() < 5 {
+            solver.new_var();
+        }
+
+        let mut models = solver.models().blocking(blocking).limit(10);
+        assert_eq!(models.by_ref().count(), 10);
+        assert!(!models.is_exhausted());
+        assert_eq!(
+            solver.models().blocking(blocking).count(),
+            match blocking {
+                // The blocking clauses of the first enumeration remain.
+                Blocking::Clauses => 22,
+                Blocking::Decisions => 32,
+            }
+        );
+    }
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use std::{
+    path::Path,
+    process::{Command, Output},
+};
+
+use common::{solver, Lcg};
+
+fn line(prefix: &str, lits: &[i32]) -> String {
+    let lits: Vec<String> = lits.iter().map(|lit| lit.to_string()).collect();
+    format!("{prefix}{} 0\n", lits.join(" "))
+}
+
+/// Run the command line driver on the file `name` with the content `input`.
+fn dissat(args: &[&str], name: &str, input: &str) -> Output {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, input).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_dissat"))
+        .args(args)
+        .arg(&path)
+        .output()
+        .unwrap()
+}
+
+/// Printed models of the solve calls, `None` for unsatisfiable calls.
+fn results(output: &Output) -> Vec<Option<Vec<i32>>> {
+    let mut results = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line == "s SATISFIABLE" {
+            results.push(Some(Vec::new()));
+        } else if line == "s UNSATISFIABLE" {
+            results.push(None);
+        } else if let Some(values) = line.strip_prefix("v ") {
+            let model: &mut Vec<i32> = results.last_mut().unwrap().as_mut().unwrap();
+            model.extend(
+                values
+                    .split_whitespace()
+                    .map(|lit| lit.parse::<i32>().unwrap())
+                    .filter(|&lit| lit != 0),
+            );
+        } else {
+            panic!("unexpected output line `{line}`");
+        }
+    }
+    results
+}
+
+/// Every solve call of a replayed iCNF file gives the same result as a fresh solver for the
+/// clauses up to that call.
+#[test]
+fn replay_matches_fresh_solvers() {
+    let mut rng = Lcg(5);
+    for workload in 0..20 {
+        let num_vars = rng.next(15) + 3;
+        let mut icnf = "c random incremental workload\np inccnf\n".to_string();
+        let mut clauses = Vec::new();
+        let mut calls = Vec::new();
+        for _ in 0..rng.next(30) + 1 {
+            if rng.next(4) == 0 {
+                let len = rng.next(4);
+                let assumptions = rng.lits(len, num_vars);
+                icnf.push_str(&line("a ", &assumptions));
+                calls.push((clauses.clone(), assumptions));
+            } else {
+                let clause = rng.clause(num_vars);
+                icnf.push_str(&line("", &clause));
+                clauses.push(clause);
+            }
+        }
+
+        let output = dissat(&[], &format!("workload{workload}.icnf"), &icnf);
+        assert!(output.status.success());
+        let results = results(&output);
+        assert_eq!(results.len(), calls.len());
+
+        for ((clauses, assumptions), result) in calls.iter().zip(results) {
+            let expected = solver(clauses).solve_with_assumptions(assumptions).is_sat();
+            assert_eq!(result.is_some(), expected, "{clauses:?} {assumptions:?}");
+
+            if let Some(model) = result {
+                assert!(assumptions.iter().all(|lit| model.contains(lit)));
+                assert!(clauses
+                    .iter()
+                    .all(|clause| clause.iter().any(|lit| model.contains(lit))));
+            }
+        }
+    }
+}
+
+#[test]
+fn other_modes_are_rejected() {
+    let icnf = "p inccnf\n1 2 0\na -1 0\n";
+    for args in [
+        &["--count"][..],
+        &["--cube", "2"],
+        &["--conquer", "2"],
+        &["--threads", "2"],
+    ] {
+        // iCNF files are recognized by their extension and by their header.
+        for name in ["rejected.icnf", "rejected.cnf"] {
+            let output = dissat(args, name, icnf);
+            assert!(!output.status.success(), "{args:?} {name}");
+            assert!(output.stdout.is_empty());
+            assert!(String::from_utf8_lossy(&output.stderr).contains("iCNF"));
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::{Arc, Mutex};
+
+use dissat::Solver;
+
+/// Clauses learned while solving `input`.
+fn learned_clauses(input: &str) -> Vec<(Vec<i32>, u32)> {
+    let learned = Arc::new(Mutex::new(Vec::new()));
+    let mut solver = Solver::from_dimacs(input).unwrap();
+    {
+        let learned = learned.clone();
+        solver.set_learn(usize::MAX, u32::MAX, move |clause, glue| {
+            learned.lock().unwrap().push((clause.to_vec(), glue))
+        });
+    }
+    solver.solve();
+
+    let learned = learned.lock().unwrap();
+    learned.clone()
+}
+
+/// Solve `input`, while continuously importing `clauses` during the search.
+fn solve_with_imports(input: &str, clauses: Vec<(Vec<i32>, u32)>) -> dissat::Result {
+    let mut solver = Solver::from_dimacs(input).unwrap();
+    let importer = solver.clause_importer();
+
+    // Use the terminate callback to import a clause whenever the solver polls it.
+    let mut clauses = clauses.into_iter();
+    solver.set_terminate(move || {
+        if let Some((clause, glue)) = clauses.next() {
+            importer.import(clause, glue);
+        }
+        false
+    });
+
+    solver.solve()
+}
+
+#[test]
+fn import_during_search_unsat() {
+    let input = std::fs::read_to_string("cnf_examples/ph6.cnf").unwrap();
+    let learned = learned_clauses(&input);
+    assert!(!learned.is_empty());
+
+    assert!(solve_with_imports(&input, learned).is_unsat());
+}
+
+#[test]
+fn import_during_search_sat() {
+    for name in ["prime1369", "sqrt5041"] {
+        let input = std::fs::read_to_string(format!("cnf_examples/{name}.cnf")).unwrap();
+        let mut learned = learned_clauses(&input);
+        learned.reverse();
+
+        let model = solve_with_imports(&input, learned).unwrap_sat();
+
+        let mut checker = Solver::from_dimacs(&input).unwrap();
+        let assumptions = model.as_vec();
+        assert!(checker.solve_with_assumptions(&assumptions).is_sat());
+    }
+}
+
+#[test]
+fn import_before_solve() {
+    let mut solver = Solver::new();
+    solver.add_clause([1, 2]);
+    solver.add_clause([-1, 2]);
+
+    let importer = solver.clause_importer();
+    assert!(importer.import(vec![2], 1));
+    assert!(solver.solve().unwrap_sat().lit(2));
+
+    assert!(importer.import(vec![-2], 1));
+    assert!(solver.solve().is_unsat());
+}
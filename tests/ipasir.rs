@@ -0,0 +1,140 @@
+use std::ffi::{c_int, c_void, CStr};
+
+use dissat::ipasir::*;
+
+fn add_clause(solver: *mut c_void, clause: &[i32]) {
+    for &lit in clause.iter().chain(&[0]) {
+        unsafe { ipasir_add(solver, lit) };
+    }
+}
+
+fn read_clauses(name: &str) -> Vec<Vec<i32>> {
+    let input = std::fs::read_to_string(format!("cnf_examples/{name}.cnf")).unwrap();
+    input
+        .lines()
+        .filter(|line| !line.starts_with('c') && !line.starts_with('p'))
+        .map(|line| {
+            line.split_whitespace()
+                .map(|lit| lit.parse::<i32>().unwrap())
+                .take_while(|&lit| lit != 0)
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn signature() {
+    let signature = unsafe { CStr::from_ptr(ipasir_signature()) };
+    assert!(signature.to_str().unwrap().starts_with("dissat"));
+}
+
+#[test]
+fn solve_examples() {
+    for name in ["sat1", "sat4", "unit2", "prime121", "prime4"] {
+        let clauses = read_clauses(name);
+
+        unsafe {
+            let solver = ipasir_init();
+            for clause in &clauses {
+                add_clause(solver, clause);
+            }
+
+            assert_eq!(ipasir_solve(solver), 10, "{name} should be satisfiable");
+            for clause in &clauses {
+                assert!(clause.iter().any(|&lit| ipasir_val(solver, lit) == lit));
+            }
+
+            ipasir_release(solver);
+        }
+    }
+}
+
+#[test]
+fn assumptions() {
+    unsafe {
+        let solver = ipasir_init();
+        add_clause(solver, &[1, 2]);
+        add_clause(solver, &[-1, 3]);
+        add_clause(solver, &[-2, 3]);
+
+        ipasir_assume(solver, -3);
+        ipasir_assume(solver, 4);
+        assert_eq!(ipasir_solve(solver), 20);
+        assert_eq!(ipasir_failed(solver, -3), 1);
+        assert_eq!(ipasir_failed(solver, 4), 0);
+        assert_eq!(ipasir_val(solver, 1), 0);
+
+        // Assumptions only hold for a single call.
+        assert_eq!(ipasir_solve(solver), 10);
+        assert_eq!(ipasir_val(solver, 3), 3);
+        assert_eq!(ipasir_val(solver, -3), 3);
+        assert_eq!(ipasir_val(solver, 5), 0);
+
+        ipasir_assume(solver, -4);
+        assert_eq!(ipasir_solve(solver), 10);
+        assert_eq!(ipasir_val(solver, 4), -4);
+
+        ipasir_release(solver);
+    }
+}
+
+#[test]
+fn terminate() {
+    unsafe extern "C" fn terminate(data: *mut c_void) -> c_int {
+        *data.cast::<u32>() += 1;
+        1
+    }
+
+    unsafe {
+        let solver = ipasir_init();
+        add_clause(solver, &[1, 2]);
+
+        let mut calls = 0u32;
+        ipasir_set_terminate(solver, (&mut calls as *mut u32).cast(), Some(terminate));
+        assert_eq!(ipasir_solve(solver), 0);
+        assert!(calls > 0);
+
+        ipasir_set_terminate(solver, std::ptr::null_mut(), None);
+        assert_eq!(ipasir_solve(solver), 10);
+
+        ipasir_release(solver);
+    }
+}
+
+#[test]
+fn learn() {
+    unsafe extern "C" fn learn(data: *mut c_void, clause: *mut i32) {
+        let clauses = &mut *data.cast::<Vec<Vec<i32>>>();
+        let mut learned = Vec::new();
+        let mut lit = clause;
+        while *lit != 0 {
+            learned.push(*lit);
+            lit = lit.add(1);
+        }
+        clauses.push(learned);
+    }
+
+    let clauses = read_clauses("ph5");
+
+    let mut learned: Vec<Vec<i32>> = Vec::new();
+    unsafe {
+        let solver = ipasir_init();
+        for clause in &clauses {
+            add_clause(solver, clause);
+        }
+
+        ipasir_set_learn(
+            solver,
+            (&mut learned as *mut Vec<Vec<i32>>).cast(),
+            4,
+            Some(learn),
+        );
+        assert_eq!(ipasir_solve(solver), 20);
+        ipasir_release(solver);
+    }
+
+    assert!(!learned.is_empty());
+    assert!(learned
+        .iter()
+        .all(|clause| !clause.is_empty() && clause.len() <= 4));
+}
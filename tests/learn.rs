@@ -0,0 +1,32 @@
+use std::sync::{Arc, Mutex};
+
+use dissat::Solver;
+
+#[test]
+fn exported_clauses_are_filtered_and_implied() {
+    let input = std::fs::read_to_string("cnf_examples/ph5.cnf").unwrap();
+
+    let learned = Arc::new(Mutex::new(Vec::new()));
+    let mut solver = Solver::from_dimacs(&input).unwrap();
+    {
+        let learned = learned.clone();
+        solver.set_learn(8, 3, move |clause, glue| {
+            learned.lock().unwrap().push((clause.to_vec(), glue));
+        });
+    }
+    assert!(solver.solve().is_unsat());
+
+    let learned = learned.lock().unwrap();
+    assert!(!learned.is_empty());
+
+    for (clause, glue) in learned.iter() {
+        assert!(clause.len() <= 8);
+        assert!((1..=3).contains(glue));
+        assert!(clause.len() > 1 || *glue == 1);
+
+        // The formula together with the negated clause has to be unsatisfiable.
+        let mut checker = Solver::from_dimacs(&input).unwrap();
+        let negated: Vec<i32> = clause.iter().map(|lit| -lit).collect();
+        assert!(checker.solve_with_assumptions(&negated).is_unsat());
+    }
+}
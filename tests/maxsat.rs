@@ -0,0 +1,86 @@
+mod common;
+
+use common::{brute_force, satisfies, Lcg};
+use dissat::maxsat::{self, MaxSatAlgorithm, MaxSatResult, Wcnf};
+
+const ALGORITHMS: [MaxSatAlgorithm; 2] = [MaxSatAlgorithm::Oll, MaxSatAlgorithm::Linear];
+
+/// Optimum cost by enumerating all assignments.
+fn optimum(wcnf: &Wcnf) -> Option<u64> {
+    brute_force(wcnf.num_vars, &wcnf.hard)
+        .into_iter()
+        .map(|assignment| {
+            wcnf.soft
+                .iter()
+                .filter(|(_, clause)| !satisfies(assignment, clause))
+                .map(|(weight, _)| weight)
+                .sum()
+        })
+        .min()
+}
+
+#[test]
+fn random_instances() {
+    let mut rng = Lcg(17);
+    let num_vars = 7;
+
+    for _ in 0..200 {
+        let wcnf = Wcnf {
+            num_vars: num_vars as usize,
+            hard: (0..rng.next(8)).map(|_| rng.clause(num_vars)).collect(),
+            soft: (0..rng.next(12) + 1)
+                .map(|_| (rng.next(6) + 1, rng.clause(num_vars)))
+                .collect(),
+        };
+        let expected = optimum(&wcnf);
+
+        for algorithm in ALGORITHMS {
+            let mut costs = Vec::new();
+            match maxsat::solve(&wcnf, algorithm, |cost| costs.push(cost)) {
+                MaxSatResult::Optimum { cost, model } => {
+                    assert_eq!(Some(cost), expected, "{algorithm:?} {wcnf:?}");
+                    assert_eq!(wcnf.cost(&model), cost);
+                    assert!(wcnf
+                        .hard
+                        .iter()
+                        .all(|clause| clause.iter().any(|&l| model.lit(l))));
+                    assert_eq!(costs.last(), Some(&cost));
+                    assert!(costs.windows(2).all(|pair| pair[0] > pair[1]));
+                }
+                MaxSatResult::Unsat => assert_eq!(expected, None, "{algorithm:?} {wcnf:?}"),
+                result => panic!("unexpected result {result:?}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn evaluation_output() {
+    // Choose one of three items with the costs 4, 2, 3, where the second excludes the third.
+    let wcnf = "p wcnf 3 6 100
+100 1 2 3 0
+100 -2 -3 0
+4 -1 0
+2 -2 0
+3 -3 0
+1 0
+";
+    let wcnf = Wcnf::parse(wcnf).unwrap();
+
+    for algorithm in ALGORITHMS {
+        let result = maxsat::solve(&wcnf, algorithm, |_| ());
+        let MaxSatResult::Optimum { cost, .. } = result else {
+            panic!("instance has a solution");
+        };
+        assert_eq!(cost, 3);
+        assert_eq!(
+            result.evaluation_output(wcnf.num_vars),
+            "s OPTIMUM FOUND\nv 010\n"
+        );
+    }
+
+    let unsat = Wcnf::parse("h 1 0\nh -1 0\n1 2 0\n").unwrap();
+    let result = maxsat::solve(&unsat, MaxSatAlgorithm::Oll, |_| ());
+    assert_eq!(result, MaxSatResult::Unsat);
+    assert_eq!(result.evaluation_output(2), "s UNSATISFIABLE\n");
+}
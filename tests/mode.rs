@@ -0,0 +1,128 @@
+mod common;
+
+use common::{assert_configs_match_brute_force, read};
+use dissat::{Config, DecisionHeuristic, Restarts, SearchMode, Solver};
+
+fn configs() -> Vec<Config> {
+    vec![
+        Config {
+            decision: DecisionHeuristic::Vmtf,
+            phase_saving: true,
+            restarts: Restarts::Glucose,
+            ..Config::default()
+        },
+        Config {
+            mode_switch_interval: Some(1),
+            ..Config::default()
+        },
+        Config {
+            seed: 7,
+            phase_saving: true,
+            rephase_interval: Some(2),
+            mode_switch_interval: Some(3),
+            ..Config::default()
+        },
+    ]
+}
+
+#[test]
+fn modes_match_brute_force() {
+    assert_configs_match_brute_force(31, 200, &configs());
+}
+
+/// Every mode after the first lasts twice as many conflicts as the previous one.
+#[test]
+fn doubling_schedule() {
+    for interval in [1, 5, 30] {
+        let mut solver = Solver::from_dimacs(&read("ph5.cnf")).unwrap();
+        solver.set_config(Config {
+            mode_switch_interval: Some(interval),
+            ..Config::default()
+        });
+        assert!(solver.solve().is_unsat());
+
+        let stats = solver.stats();
+        let mut remaining = stats.focused.conflicts + stats.stable.conflicts;
+        let (mut focused, mut stable, mut switches) = (0, 0, 0);
+        let mut length = interval;
+        loop {
+            let conflicts = remaining.min(length);
+            if switches % 2 == 0 {
+                focused += conflicts;
+            } else {
+                stable += conflicts;
+            }
+            remaining -= conflicts;
+            if conflicts < length {
+                break;
+            }
+            switches += 1;
+            length *= 2;
+        }
+        assert!(switches > 1, "{interval}");
+        assert_eq!(stats.mode_switches, switches, "{interval}");
+        assert_eq!(stats.focused.conflicts, focused, "{interval}");
+        assert_eq!(stats.stable.conflicts, stable, "{interval}");
+    }
+}
+
+#[test]
+fn mode_stats_add_up() {
+    for name in ["ph5.cnf", "add32.cnf", "prime1369.cnf"] {
+        let mut solver = Solver::from_dimacs(&read(name)).unwrap();
+        solver.set_config(Config {
+            phase_saving: true,
+            mode_switch_interval: Some(20),
+            ..Config::default()
+        });
+        assert_eq!(solver.search_mode(), Some(SearchMode::Focused));
+        assert!(!solver.solve().is_unknown());
+
+        let stats = solver.stats();
+        assert!(stats.mode_switches > 0, "{name}");
+        assert!(
+            stats.focused.conflicts > 0 && stats.stable.conflicts > 0,
+            "{name}"
+        );
+        assert_eq!(
+            stats.focused.decisions + stats.stable.decisions,
+            stats.decisions,
+            "{name}"
+        );
+        assert_eq!(
+            stats.focused.restarts + stats.stable.restarts,
+            stats.restarts,
+            "{name}"
+        );
+        // Only a final conflict on decision level zero isn't assigned to a mode.
+        assert!(stats.focused.conflicts + stats.stable.conflicts + 1 >= stats.contradictions);
+        // The modes alternate, starting with focused mode.
+        let expected_mode = if stats.mode_switches.is_multiple_of(2) {
+            SearchMode::Focused
+        } else {
+            SearchMode::Stable
+        };
+        assert_eq!(solver.search_mode(), Some(expected_mode), "{name}");
+    }
+}
+
+#[test]
+fn no_modes_by_default() {
+    let mut solver = Solver::from_dimacs(&read("ph5.cnf")).unwrap();
+    assert_eq!(solver.search_mode(), None);
+    assert!(solver.solve().is_unsat());
+
+    let stats = solver.stats();
+    assert!(stats.decisions > 0);
+    assert_eq!(stats.mode_switches, 0);
+    assert_eq!(stats.focused.decisions + stats.stable.decisions, 0);
+}
+
+#[test]
+fn zero_mode_switch_interval_is_rejected() {
+    let config = Config {
+        mode_switch_interval: Some(0),
+        ..Config::default()
+    };
+    assert!(std::panic::catch_unwind(|| Solver::with_config(config)).is_err());
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::{read_clauses, Lcg};
+use dissat::{
+    mus::{self, MusResult},
+    Solver,
+};
+
+fn is_sat(clauses: &[Vec<i32>], subset: impl Iterator<Item = usize>) -> bool {
+    let mut solver = Solver::new();
+    for idx in subset {
+        solver.add_clause(clauses[idx].iter().copied());
+    }
+    solver.solve().is_sat()
+}
+
+/// Check that `mus` is unsatisfiable and every clause of it is necessary.
+fn assert_minimal(clauses: &[Vec<i32>], mus: &[usize]) {
+    assert!(mus.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(!is_sat(clauses, mus.iter().copied()));
+    for &removed in mus {
+        assert!(
+            is_sat(clauses, mus.iter().copied().filter(|&idx| idx != removed)),
+            "clause {removed} isn't necessary"
+        );
+    }
+}
+
+#[test]
+fn mus_of_examples() {
+    for (name, size) in [
+        ("false.cnf", Some(1)),
+        ("full3.cnf", Some(8)),
+        ("full5.cnf", Some(32)),
+        ("ph3.cnf", None),
+        ("ph4.cnf", None),
+        ("add4.cnf", None),
+    ] {
+        let clauses = read_clauses(name);
+        match mus::extract(&clauses) {
+            MusResult::Mus(mus) => {
+                assert_minimal(&clauses, &mus);
+                if let Some(size) = size {
+                    assert_eq!(mus.len(), size, "{name}");
+                }
+            }
+            MusResult::Satisfiable(_) => panic!("{name} is unsat"),
+        }
+    }
+}
+
+#[test]
+fn mus_names_input_clauses() {
+    // Clauses 1, 3 and 4 conflict, clause 0 and 2 are irrelevant.
+    let clauses = vec![vec![5, 6], vec![1, 2], vec![-5, 3], vec![-1], vec![-2]];
+    assert_eq!(mus::extract(&clauses), MusResult::Mus(vec![1, 3, 4]));
+}
+
+#[test]
+fn satisfiable_model_has_no_selectors() {
+    let clauses = vec![vec![1, 3], vec![-1], vec![-3, 2]];
+    match mus::extract(&clauses) {
+        MusResult::Satisfiable(model) => assert_eq!(model.as_vec(), vec![-1, 2, 3]),
+        result => panic!("unexpected result {result:?}"),
+    }
+}
+
+#[test]
+fn mus_of_random_formulas() {
+    let mut rng = Lcg(7);
+    let mut unsat = 0;
+    for _ in 0..300 {
+        let num_vars = rng.next(8) + 1;
+        let num_clauses = rng.next(6 * num_vars) + 1;
+        let clauses: Vec<Vec<i32>> = (0..num_clauses).map(|_| rng.clause(num_vars)).collect();
+
+        match mus::extract(&clauses) {
+            MusResult::Mus(mus) => {
+                unsat += 1;
+                assert_minimal(&clauses, &mus);
+            }
+            MusResult::Satisfiable(model) => {
+                assert!(model.num_vars() <= num_vars as usize);
+                assert!(clauses
+                    .iter()
+                    .all(|clause| clause.iter().any(|&lit| model.lit(lit))));
+            }
+        }
+    }
+    assert!(unsat > 50);
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use common::{satisfies, Lcg};
+use dissat::{Opb, PbConstraint, PbEncoding, Relation, Solver};
+
+const ENCODINGS: [PbEncoding; 4] = [
+    PbEncoding::Bdd,
+    PbEncoding::SortingNetwork,
+    PbEncoding::Totalizer,
+    PbEncoding::Adder,
+];
+
+fn random_constraint(rng: &mut Lcg, num_vars: u64) -> PbConstraint {
+    let terms: Vec<(i64, i32)> = (0..rng.next(5) + 1)
+        .map(|_| {
+            let coefficient = rng.next(9) as i64 - 4;
+            let var = rng.next(num_vars) as i32 + 1;
+            (coefficient, if rng.next(2) == 0 { var } else { -var })
+        })
+        .collect();
+    let relation = [Relation::GreaterEq, Relation::Eq, Relation::LessEq][rng.next(3) as usize];
+    let rhs = rng.next(9) as i64 - 3;
+
+    PbConstraint {
+        terms,
+        relation,
+        rhs,
+    }
+}
+
+fn is_satisfied(constraint: &PbConstraint, assignment: u32) -> bool {
+    let sum: i64 = constraint
+        .terms
+        .iter()
+        .filter(|(_, lit)| satisfies(assignment, &[*lit]))
+        .map(|(coefficient, _)| coefficient)
+        .sum();
+
+    match constraint.relation {
+        Relation::GreaterEq => sum >= constraint.rhs,
+        Relation::Eq => sum == constraint.rhs,
+        Relation::LessEq => sum <= constraint.rhs,
+    }
+}
+
+#[test]
+fn random_pb_constraints() {
+    let mut rng = Lcg(3);
+    let num_vars = 8;
+
+    for _ in 0..200 {
+        let constraints: Vec<PbConstraint> = (0..rng.next(4) + 1)
+            .map(|_| random_constraint(&mut rng, num_vars))
+            .collect();
+
+        let satisfiable = (0..1 << num_vars)
+            .any(|assignment| constraints.iter().all(|c| is_satisfied(c, assignment)));
+
+        for encoding in ENCODINGS {
+            // Reserve the variables of the instance, before auxiliary variables are allocated.
+            let mut solver = Solver::new();
+            while solver.num_vars() < num_vars as usize {
+                solver.new_var();
+            }
+            for constraint in &constraints {
+                solver.add_pb_constraint(constraint, encoding).unwrap();
+            }
+
+            let result = solver.solve();
+            assert_eq!(result.is_sat(), satisfiable, "{encoding:?} {constraints:?}");
+
+            if let dissat::Result::Sat(model) = result {
+                let assignment = (1..=num_vars as i32)
+                    .filter(|&var| model.lit(var))
+                    .fold(0, |assignment, var| assignment | 1 << (var - 1));
+                assert!(constraints.iter().all(|c| is_satisfied(c, assignment)));
+            }
+        }
+    }
+}
+
+#[test]
+fn solve_opb_file() {
+    // Choose at least two of four items with weights 3, 5, 4, 2 and a total weight of at most 7,
+    // where the first two items exclude each other.
+    let opb = "
+    * #variable= 4 #constraint= 3
+    +1 x1 +1 x2 +1 x3 +1 x4 >= 2 ;
+    +3 x1 +5 x2 +4 x3 +2 x4 <= 7 ;
+    +1 ~x1 +1 ~x2 >= 1 ;
+    ";
+
+    let instance = Opb::parse(opb).unwrap();
+    for encoding in ENCODINGS {
+        let model = Solver::from_opb(opb, encoding)
+            .unwrap()
+            .solve()
+            .unwrap_sat();
+
+        let assignment = (1..=4)
+            .filter(|&var| model.lit(var))
+            .fold(0, |assignment, var| assignment | 1 << (var - 1));
+        assert!(instance
+            .constraints
+            .iter()
+            .all(|c| is_satisfied(c, assignment)));
+    }
+
+    // All pairs of items are too heavy.
+    let opb = "+1 x1 +1 x2 +1 x3 >= 2 ;\n+4 x1 +5 x2 +4 x3 <= 7 ;\n";
+    for encoding in ENCODINGS {
+        assert!(Solver::from_opb(opb, encoding).unwrap().solve().is_unsat());
+    }
+}
+
+#[test]
+fn large_coefficients() {
+    // Exactly one of the heavy items and the light item.
+    let opb = "
+    +1000000000 x1 +1000000000 x2 +1 x3 >= 1000000001 ;
+    +1 x1 +1 x2 <= 1 ;
+    ";
+
+    let instance = Opb::parse(opb).unwrap();
+    for encoding in ENCODINGS {
+        let model = Solver::from_opb(opb, encoding)
+            .unwrap()
+            .solve()
+            .unwrap_sat();
+
+        let assignment = (1..=3)
+            .filter(|&var| model.lit(var))
+            .fold(0, |assignment, var| assignment | 1 << (var - 1));
+        assert!(instance
+            .constraints
+            .iter()
+            .all(|c| is_satisfied(c, assignment)));
+    }
+
+    let opb = format!("{opb}+1 ~x3 >= 1 ;\n");
+    for encoding in ENCODINGS {
+        assert!(Solver::from_opb(&opb, encoding).unwrap().solve().is_unsat());
+    }
+}
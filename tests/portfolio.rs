@@ -0,0 +1,104 @@
+mod common;
+
+use std::{thread, time::Duration};
+
+use common::{assert_configs_match_brute_force, brute_force_sat, pigeonhole, read_clauses, Lcg};
+use dissat::{portfolio::Portfolio, Config, DecisionHeuristic, Phase, Restarts, Solver};
+
+fn configs() -> Vec<Config> {
+    vec![
+        Config::default(),
+        Config {
+            seed: 3,
+            decision: DecisionHeuristic::Vsids,
+            phase: Phase::Random,
+            phase_saving: true,
+            restarts: Restarts::Luby { unit: 1 },
+            target_phases: true,
+            rephase_interval: Some(5),
+            ..Config::default()
+        },
+        Config {
+            phase_saving: true,
+            mode_switch_interval: Some(3),
+            ..Config::default()
+        },
+        Config {
+            decision: DecisionHeuristic::Vsids,
+            phase: Phase::Negative,
+            restarts: Restarts::Luby { unit: 4 },
+            ..Config::default()
+        },
+    ]
+}
+
+/// Single solvers with each configuration agree with brute force.
+#[test]
+fn configs_match_brute_force() {
+    assert_configs_match_brute_force(13, 300, &configs());
+}
+
+#[test]
+fn portfolio_matches_brute_force() {
+    let mut rng = Lcg(17);
+    for _ in 0..100 {
+        let num_vars = rng.next(12) + 1;
+        let num_clauses = rng.next(6 * num_vars);
+        let clauses: Vec<Vec<i32>> = (0..num_clauses).map(|_| rng.lits(3, num_vars)).collect();
+
+        // Share everything, so imported clauses are exercised even on tiny instances.
+        let portfolio = Portfolio::with_configs(configs()).share(16, u32::MAX);
+        let result = portfolio.solve(&clauses);
+        assert_eq!(
+            result.is_sat(),
+            brute_force_sat(num_vars as usize, &clauses, &[])
+        );
+        if let dissat::Result::Sat(model) = result {
+            assert!(clauses
+                .iter()
+                .all(|clause| clause.iter().any(|&lit| model.lit(lit))));
+        }
+    }
+}
+
+#[test]
+fn portfolio_examples() {
+    for (name, sat) in [
+        ("ph4.cnf", false),
+        ("ph5.cnf", false),
+        ("full5.cnf", false),
+        ("add32.cnf", false),
+        ("prime169.cnf", true),
+        ("prime961.cnf", true),
+        ("sat13.cnf", true),
+    ] {
+        for threads in [1, 4] {
+            let result = Portfolio::new(threads).solve(&read_clauses(name));
+            assert_eq!(result.is_sat(), sat, "{name} with {threads} threads");
+        }
+    }
+}
+
+#[test]
+fn terminate_portfolio() {
+    // The pigeonhole formula with 10 pigeons is far out of reach.
+    let clauses = pigeonhole(10, 9);
+
+    let portfolio = Portfolio::new(3);
+    let handle = portfolio.terminate_handle();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        handle.terminate();
+    });
+    assert!(portfolio.solve(&clauses).is_unknown());
+}
+
+#[test]
+fn zero_luby_unit_is_rejected() {
+    let config = Config {
+        restarts: Restarts::Luby { unit: 0 },
+        ..Config::default()
+    };
+    assert!(std::panic::catch_unwind(|| Solver::with_config(config.clone())).is_err());
+    assert!(std::panic::catch_unwind(|| Portfolio::with_configs(vec![config])).is_err());
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use dissat::{Solver, UserPropagator};
+
+/// Keeps track of the values of the observed variables.
+#[derive(Default)]
+struct Assignment {
+    values: HashMap<i32, bool>,
+    trail: Vec<i32>,
+    level_starts: Vec<usize>,
+}
+
+impl Assignment {
+    fn assign(&mut self, lits: &[i32]) {
+        for &lit in lits {
+            self.values.insert(lit.abs(), lit > 0);
+            self.trail.push(lit.abs());
+        }
+    }
+
+    fn new_decision_level(&mut self) {
+        self.level_starts.push(self.trail.len());
+    }
+
+    fn backtrack(&mut self, new_level: u32) {
+        let start = self.level_starts[new_level as usize];
+        for var in self.trail.drain(start..) {
+            self.values.remove(&var);
+        }
+        self.level_starts.truncate(new_level as usize);
+    }
+
+    fn value(&self, var: i32) -> Option<bool> {
+        self.values.get(&var).copied()
+    }
+}
+
+/// Enforces that at most one variable of each group is true.
+struct AtMostOne {
+    groups: Vec<Vec<i32>>,
+    assignment: Assignment,
+}
+
+impl AtMostOne {
+    fn true_vars<'a>(&'a self, group: &'a [i32]) -> impl Iterator<Item = i32> + 'a {
+        group
+            .iter()
+            .copied()
+            .filter(|&var| self.assignment.value(var) == Some(true))
+    }
+}
+
+impl UserPropagator for AtMostOne {
+    fn notify_assignment(&mut self, lits: &[i32]) {
+        self.assignment.assign(lits);
+    }
+
+    fn notify_new_decision_level(&mut self) {
+        self.assignment.new_decision_level();
+    }
+
+    fn notify_backtrack(&mut self, new_level: u32) {
+        self.assignment.backtrack(new_level);
+    }
+
+    fn propagate(&mut self) -> Vec<i32> {
+        let mut implied = Vec::new();
+        for group in &self.groups {
+            if let Some(true_var) = self.true_vars(group).next() {
+                implied.extend(
+                    group
+                        .iter()
+                        .filter(|&&var| var != true_var)
+                        .map(|&var| -var),
+                );
+            }
+        }
+        implied
+    }
+
+    fn add_reason_clause(&mut self, propagated_lit: i32) -> Vec<i32> {
+        let var = propagated_lit.abs();
+        let group = self
+            .groups
+            .iter()
+            .find(|group| group.contains(&var))
+            .unwrap();
+        let true_var = self.true_vars(group).find(|&other| other != var).unwrap();
+        vec![propagated_lit, -true_var]
+    }
+
+    fn check_model(&mut self, model: &[i32]) -> bool {
+        self.groups.iter().all(|group| {
+            group
+                .iter()
+                .filter(|&&var| model[var as usize - 1] > 0)
+                .count()
+                <= 1
+        })
+    }
+}
+
+/// Pigeonhole problem, where the at-most-one constraints are handled by the propagator.
+fn pigeonhole(pigeons: i32, holes: i32) -> Solver {
+    let var = |pigeon: i32, hole: i32| pigeon * holes + hole + 1;
+
+    let mut solver = Solver::new();
+    for pigeon in 0..pigeons {
+        solver.add_clause((0..holes).map(|hole| var(pigeon, hole)));
+    }
+
+    let groups: Vec<Vec<i32>> = (0..holes)
+        .map(|hole| (0..pigeons).map(|pigeon| var(pigeon, hole)).collect())
+        .collect();
+
+    solver.connect_propagator(Box::new(AtMostOne {
+        groups: groups.clone(),
+        assignment: Assignment::default(),
+    }));
+    for var in groups.into_iter().flatten() {
+        solver.add_observed_var(var);
+    }
+
+    solver
+}
+
+#[test]
+fn pigeonhole_unsat() {
+    assert!(pigeonhole(5, 4).solve().is_unsat());
+    assert!(pigeonhole(7, 6).solve().is_unsat());
+}
+
+#[test]
+fn pigeonhole_sat() {
+    let holes = 6;
+    let model = pigeonhole(6, holes).solve().unwrap_sat();
+
+    for hole in 1..=holes {
+        let occupied = (0..6)
+            .filter(|pigeon| model.lit(pigeon * holes + hole))
+            .count();
+        assert_eq!(occupied, 1);
+    }
+}
+
+/// Rejects models with an odd number of true variables by adding the negated model.
+#[derive(Default)]
+struct EvenParity {
+    rejected: Vec<Vec<i32>>,
+}
+
+impl UserPropagator for EvenParity {
+    fn add_reason_clause(&mut self, _propagated_lit: i32) -> Vec<i32> {
+        unreachable!("EvenParity never propagates")
+    }
+
+    fn check_model(&mut self, model: &[i32]) -> bool {
+        if model.iter().filter(|&&lit| lit > 0).count() % 2 == 0 {
+            return true;
+        }
+        self.rejected.push(model.iter().map(|&lit| -lit).collect());
+        false
+    }
+
+    fn add_external_clause(&mut self) -> Option<Vec<i32>> {
+        self.rejected.pop()
+    }
+}
+
+#[test]
+fn check_model_rejects() {
+    let mut solver = Solver::new();
+    solver.add_clause([1, 2, 3]);
+    solver.add_clause([-1, -2]);
+    solver.connect_propagator(Box::new(EvenParity::default()));
+
+    let model = solver.solve().unwrap_sat();
+    assert_eq!(model.iter().filter(|&lit| lit > 0).count() % 2, 0);
+
+    // Only odd models remain.
+    solver.add_clause([-1, -3]);
+    solver.add_clause([-2, -3]);
+    assert!(solver.solve().is_unsat());
+}
+
+/// Rejects every model, without providing a clause.
+struct RejectAll;
+
+impl UserPropagator for RejectAll {
+    fn add_reason_clause(&mut self, _propagated_lit: i32) -> Vec<i32> {
+        unreachable!("RejectAll never propagates")
+    }
+
+    fn check_model(&mut self, _model: &[i32]) -> bool {
+        false
+    }
+}
+
+#[test]
+fn rejection_without_clause_is_unknown() {
+    let mut solver = Solver::new();
+    solver.add_clause([1, 2]);
+    solver.connect_propagator(Box::new(RejectAll));
+    assert!(solver.solve().is_unknown());
+}
+
+/// Always suggests the same decisions.
+struct Decide(Vec<i32>);
+
+impl UserPropagator for Decide {
+    fn add_reason_clause(&mut self, _propagated_lit: i32) -> Vec<i32> {
+        unreachable!("Decide never propagates")
+    }
+
+    fn decide(&mut self) -> Option<i32> {
+        self.0.pop()
+    }
+}
+
+#[test]
+fn decisions_of_propagator() {
+    let mut solver = Solver::new();
+    solver.add_clause([1, 2]);
+    solver.add_clause([-2, 3]);
+    solver.connect_propagator(Box::new(Decide(vec![-3, -1])));
+
+    let model = solver.solve().unwrap_sat();
+    assert_eq!(model.as_vec(), vec![-1, 2, 3]);
+
+    let propagator = solver.disconnect_propagator();
+    assert!(propagator.is_some());
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::{assert_configs_match_brute_force, pigeonhole, solver};
+use dissat::{Config, DecisionHeuristic, Phase, Restarts, Solver};
+
+/// Rephasing after almost every conflict, with and without target phases.
+fn configs() -> Vec<Config> {
+    let mut configs = Vec::new();
+    for (seed, phase) in [
+        (0, Phase::Positive),
+        (5, Phase::Negative),
+        (9, Phase::Random),
+    ] {
+        for target_phases in [false, true] {
+            configs.push(Config {
+                seed,
+                decision: DecisionHeuristic::Vsids,
+                phase,
+                phase_saving: true,
+                target_phases,
+                restarts: Restarts::Luby { unit: 2 },
+                rephase_interval: Some(1),
+                ..Config::default()
+            });
+        }
+    }
+    configs
+}
+
+#[test]
+fn rephasing_matches_brute_force() {
+    assert_configs_match_brute_force(29, 200, &configs());
+}
+
+/// The k-th rephasing happens k intervals after the previous one.
+#[test]
+fn arithmetic_schedule() {
+    for interval in [1, 7, 20] {
+        let mut solver = solver(&pigeonhole(6, 5));
+        solver.set_config(Config {
+            decision: DecisionHeuristic::Vsids,
+            phase_saving: true,
+            rephase_interval: Some(interval),
+            ..Config::default()
+        });
+        assert!(solver.solve().is_unsat());
+
+        // The final conflict on decision level zero doesn't count.
+        let conflicts = solver.stats().contradictions - 1;
+        let expected = (1..)
+            .take_while(|&k| interval * k * (k + 1) / 2 <= conflicts)
+            .count() as u64;
+        assert!(expected > 1, "{interval}");
+        assert_eq!(solver.stats().rephases, expected, "{interval}");
+    }
+}
+
+#[test]
+fn zero_rephase_interval_is_rejected() {
+    let config = Config {
+        rephase_interval: Some(0),
+        ..Config::default()
+    };
+    assert!(std::panic::catch_unwind(|| Solver::with_config(config)).is_err());
+}
+
+#[test]
+fn no_rephasing_by_default() {
+    // The pigeonhole formula with 5 pigeons needs plenty of conflicts.
+    let mut solver = solver(&pigeonhole(5, 4));
+    assert!(solver.solve().is_unsat());
+    assert!(solver.stats().contradictions > 0);
+    assert_eq!(solver.stats().rephases, 0);
+    assert_eq!(solver.stats().walks, 0);
+}
@@ -0,0 +1,63 @@
+use std::{thread, time::Duration};
+
+use dissat::Solver;
+
+/// Pigeonhole formula with `holes + 1` pigeons, which is hard for resolution based solvers.
+fn pigeonhole(holes: i32) -> Solver {
+    let var = |pigeon: i32, hole: i32| pigeon * holes + hole + 1;
+
+    let mut solver = Solver::new();
+    for pigeon in 0..=holes {
+        solver.add_clause((0..holes).map(|hole| var(pigeon, hole)));
+    }
+    for hole in 0..holes {
+        for p1 in 0..=holes {
+            for p2 in p1 + 1..=holes {
+                solver.add_clause([-var(p1, hole), -var(p2, hole)]);
+            }
+        }
+    }
+    solver
+}
+
+#[test]
+fn terminate_from_other_thread() {
+    let mut solver = pigeonhole(12);
+    let handle = solver.terminate_handle();
+
+    let worker = thread::spawn(move || {
+        let result = solver.solve();
+        (solver, result)
+    });
+
+    thread::sleep(Duration::from_millis(50));
+    handle.terminate();
+
+    let (mut solver, result) = worker.join().unwrap();
+    assert!(result.is_unknown());
+
+    // The flag stays set until it is reset.
+    assert!(solver.solve().is_unknown());
+    handle.reset();
+
+    let mut calls = 0;
+    solver.set_terminate(move || {
+        calls += 1;
+        calls > 100
+    });
+    assert!(solver.solve().is_unknown());
+}
+
+#[test]
+fn solver_is_reusable_after_termination() {
+    let mut solver = Solver::new();
+    solver.add_clause([1, 2]);
+    solver.add_clause([-1, 2]);
+
+    solver.set_terminate(|| true);
+    assert!(solver.solve().is_unknown());
+
+    solver.clear_terminate();
+    let model = solver.solve().unwrap_sat();
+    assert!(model.lit(2));
+}
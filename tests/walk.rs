@@ -0,0 +1,97 @@
+mod common;
+
+use common::{brute_force, read, solver, Lcg};
+use dissat::{Config, DecisionHeuristic, Model, Restarts, Solver};
+
+/// Random 3-SAT clause, which is satisfied by `hidden`.
+fn planted_clause(rng: &mut Lcg, hidden: &[bool]) -> Vec<i32> {
+    loop {
+        let clause = rng.lits(3, hidden.len() as u64);
+        if clause
+            .iter()
+            .any(|&lit| hidden[lit.unsigned_abs() as usize - 1] == (lit > 0))
+        {
+            return clause;
+        }
+    }
+}
+
+fn is_model(model: &Model, clauses: &[Vec<i32>]) -> bool {
+    clauses
+        .iter()
+        .all(|clause| clause.iter().any(|&lit| model.lit(lit)))
+}
+
+#[test]
+fn walk_finds_planted_models() {
+    let mut rng = Lcg(19);
+    for _ in 0..5 {
+        let num_vars = 300;
+        let hidden: Vec<bool> = (0..num_vars).map(|_| rng.next(2) == 0).collect();
+        let clauses: Vec<Vec<i32>> = (0..4 * num_vars)
+            .map(|_| planted_clause(&mut rng, &hidden))
+            .collect();
+
+        let mut solver = solver(&clauses);
+        let model = solver.walk(1_000_000).expect("no model found");
+        assert!(is_model(&model, &clauses));
+        assert_eq!(solver.stats().walks, 1);
+        assert!(solver.stats().walk_flips > 0);
+    }
+}
+
+#[test]
+fn walk_models_are_models() {
+    let mut rng = Lcg(23);
+    for _ in 0..300 {
+        let num_vars = rng.next(12) + 1;
+        let num_clauses = rng.next(6 * num_vars);
+        let mut clauses: Vec<Vec<i32>> = (0..num_clauses).map(|_| rng.lits(3, num_vars)).collect();
+        // Units are fixed by propagation before the search.
+        if rng.next(2) == 0 {
+            clauses.push(vec![rng.next(num_vars) as i32 + 1]);
+        }
+
+        let mut solver = solver(&clauses);
+        while solver.num_vars() < num_vars as usize {
+            solver.new_var();
+        }
+
+        let sat = !brute_force(num_vars as usize, &clauses).is_empty();
+        match solver.walk(10_000) {
+            Some(model) => {
+                assert!(sat);
+                assert!(is_model(&model, &clauses));
+            }
+            // Small satisfiable formulas are always found within the flips.
+            None => assert!(!sat),
+        }
+
+        // The walk doesn't change the answer of the CDCL search.
+        assert_eq!(solver.solve().is_sat(), sat);
+    }
+}
+
+#[test]
+fn rephasing_with_walks() {
+    let config = Config {
+        decision: DecisionHeuristic::Vsids,
+        phase_saving: true,
+        restarts: Restarts::Luby { unit: 10 },
+        rephase_interval: Some(10),
+        ..Config::default()
+    };
+    for (name, sat) in [
+        ("ph5.cnf", false),
+        ("add32.cnf", false),
+        ("prime961.cnf", true),
+        ("prime1369.cnf", true),
+    ] {
+        let mut solver = Solver::from_dimacs(&read(name)).unwrap();
+        solver.set_config(config.clone());
+        assert_eq!(solver.solve().is_sat(), sat, "{name}");
+        // Every fourth rephasing runs local search.
+        assert_eq!(solver.stats().walks, solver.stats().rephases / 4, "{name}");
+        assert!(solver.stats().walks > 0, "{name}");
+    }
+}
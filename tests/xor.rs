@@ -0,0 +1,118 @@
+mod common;
+
+use common::{satisfies, Lcg};
+use dissat::Solver;
+
+fn xor_satisfied(xor: &[i32], assignment: u32) -> bool {
+    xor.iter()
+        .filter(|&&lit| (assignment >> (lit.unsigned_abs() - 1) & 1 == 1) == (lit > 0))
+        .count()
+        % 2
+        == 1
+}
+
+#[test]
+fn random_xor_systems() {
+    let mut rng = Lcg(42);
+    let num_vars = 10;
+
+    for _ in 0..300 {
+        let xors: Vec<Vec<i32>> = (0..rng.next(8) + 1)
+            .map(|_| {
+                let len = rng.next(5) + 1;
+                rng.lits(len, num_vars)
+            })
+            .collect();
+        let clauses: Vec<Vec<i32>> = (0..rng.next(20)).map(|_| rng.lits(3, num_vars)).collect();
+
+        let mut solver = Solver::new();
+        for xor in &xors {
+            solver.add_xor(xor.iter().copied());
+        }
+        for clause in &clauses {
+            solver.add_clause(clause.iter().copied());
+        }
+
+        let satisfiable = (0..1 << num_vars).any(|assignment| {
+            xors.iter().all(|xor| xor_satisfied(xor, assignment))
+                && clauses.iter().all(|clause| satisfies(assignment, clause))
+        });
+
+        let result = solver.solve();
+        assert_eq!(result.is_sat(), satisfiable, "{xors:?} {clauses:?}");
+
+        if let dissat::Result::Sat(model) = result {
+            for xor in &xors {
+                let count = xor.iter().filter(|&&lit| model.lit(lit)).count();
+                assert_eq!(count % 2, 1, "{xor:?} is not satisfied by {model:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn xor_cnf_dimacs() {
+    // x1 ⊕ x2 ⊕ x3 = 1, x1 = x2, x2 ⊕ x3 = 1 and x1 ⊕ x3 = 0 are contradictory.
+    let input = "p cnf 3 4\nx 1 2 3 0\nx 1 -2 0\nx 2 3 0\nx -1 3 0\n";
+    assert!(Solver::from_dimacs(input).unwrap().solve().is_unsat());
+
+    let input = "p cnf 3 3\nx 1 2 3 0\nx1 -2 0\n-1 2 0\n";
+    let model = Solver::from_dimacs(input).unwrap().solve().unwrap_sat();
+    assert!(model.lit(3));
+    assert_eq!(model.lit(1), model.lit(2));
+}
+
+/// Xor constraints added between calls with assumptions, so the matrix is eliminated again and
+/// reused after backtracking.
+#[test]
+fn incremental_xor_systems() {
+    let mut rng = Lcg(7);
+    let num_vars = 10;
+
+    for _ in 0..100 {
+        let clauses: Vec<Vec<i32>> = (0..rng.next(15)).map(|_| rng.lits(3, num_vars)).collect();
+        let mut xors: Vec<Vec<i32>> = Vec::new();
+
+        let mut solver = Solver::new();
+        for clause in &clauses {
+            solver.add_clause(clause.iter().copied());
+        }
+
+        for _ in 0..4 {
+            for _ in 0..rng.next(3) + 1 {
+                let len = rng.next(5) + 1;
+                let xor = rng.lits(len, num_vars);
+                solver.add_xor(xor.iter().copied());
+                xors.push(xor);
+            }
+
+            for _ in 0..5 {
+                let len = rng.next(4);
+                let assumptions = rng.lits(len, num_vars);
+
+                let satisfiable = (0..1 << num_vars).any(|assignment| {
+                    assumptions.iter().all(|&lit| satisfies(assignment, &[lit]))
+                        && xors.iter().all(|xor| xor_satisfied(xor, assignment))
+                        && clauses.iter().all(|clause| satisfies(assignment, clause))
+                });
+
+                let result = solver.solve_with_assumptions(&assumptions);
+                assert_eq!(result.is_sat(), satisfiable, "{xors:?} {clauses:?}");
+
+                if let dissat::Result::Sat(model) = result {
+                    // Variables, which only appear twice in a xor, are missing in the model.
+                    let assignment = (1..=num_vars as i32)
+                        .filter(|&var| model.lit(var))
+                        .fold(0, |assignment, var| assignment | 1 << (var - 1));
+                    assert!(assumptions.iter().all(|&lit| model.lit(lit)));
+                    for xor in &xors {
+                        assert!(
+                            xor_satisfied(xor, assignment),
+                            "{xor:?} is not satisfied by {model:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}